@@ -5,9 +5,95 @@ extern crate wasm_game_of_life;
 
 #[bench]
 fn universe_ticks(b: &mut test::Bencher) {
-    let mut universe = wasm_game_of_life::Universe::new(64, 64);
+    let mut universe = wasm_game_of_life::Universe::new(64, 64).unwrap();
 
     b.iter(|| {
         universe.tick();
     });
 }
+
+#[bench]
+fn large_universe_ticks(b: &mut test::Bencher) {
+    // 1024 x 1024 cells pack into 128KiB per buffer, down from 1MiB with a
+    // byte per cell.
+    let mut universe = wasm_game_of_life::Universe::new(1024, 1024).unwrap();
+
+    b.iter(|| {
+        universe.tick();
+    });
+}
+
+#[bench]
+fn large_universe_lut_ticks(b: &mut test::Bencher) {
+    let mut universe = wasm_game_of_life::Universe::new(1024, 1024).unwrap();
+
+    b.iter(|| {
+        universe.tick_lut();
+    });
+}
+
+const GOSPER_GLIDER_GUN_RLE: &str = "x = 36, y = 9, rule = B3/S23
+24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$
+10bo5bo7bo$11bo3bo$12b2o!";
+
+fn glider_gun_universe() -> wasm_game_of_life::Universe {
+    let mut universe = wasm_game_of_life::Universe::new_dead(256, 256).unwrap();
+    universe.insert_rle(10, 10, GOSPER_GLIDER_GUN_RLE).unwrap();
+    universe
+}
+
+#[bench]
+fn sparse_universe_ticks(b: &mut test::Bencher) {
+    let mut universe = glider_gun_universe();
+
+    b.iter(|| {
+        universe.tick();
+    });
+}
+
+#[bench]
+fn sparse_universe_incremental_ticks(b: &mut test::Bencher) {
+    let mut universe = glider_gun_universe();
+
+    b.iter(|| {
+        universe.tick_incremental();
+    });
+}
+
+#[bench]
+fn single_glider_ticks(b: &mut test::Bencher) {
+    let mut universe = wasm_game_of_life::Universe::new_dead(512, 512).unwrap();
+    universe.insert_glider(10, 10, wasm_game_of_life::GliderDirection::SE);
+
+    b.iter(|| {
+        universe.tick();
+    });
+}
+
+fn pulsar_grid_universe() -> wasm_game_of_life::Universe {
+    let mut universe = wasm_game_of_life::Universe::new_dead(128, 128).unwrap();
+    for row in (8..128).step_by(16) {
+        for col in (8..128).step_by(16) {
+            universe.insert_pulsar(row, col);
+        }
+    }
+    universe
+}
+
+#[bench]
+fn pulsar_grid_128_ticks(b: &mut test::Bencher) {
+    let mut universe = pulsar_grid_universe();
+
+    b.iter(|| {
+        universe.tick_n(128);
+    });
+}
+
+#[bench]
+fn pulsar_grid_128_hashed_ticks(b: &mut test::Bencher) {
+    let mut universe = pulsar_grid_universe();
+
+    b.iter(|| {
+        universe.tick_hashed(128);
+    });
+}
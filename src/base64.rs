@@ -0,0 +1,49 @@
+/// The URL-safe base64 alphabet, so encoded universes can go in a URL
+/// without escaping.
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encode bytes as unpadded URL-safe base64.
+pub fn encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | (byte as u32) << (16 - 8 * i)
+        });
+
+        // Each byte needs at least two characters, and every further byte
+        // one more.
+        for i in 0..=chunk.len() {
+            let sextet = (group >> (18 - 6 * i)) & 0x3f;
+            encoded.push(ALPHABET[sextet as usize] as char);
+        }
+    }
+
+    encoded
+}
+
+/// Decode URL-safe base64, with or without `=` padding.
+pub fn decode(text: &str) -> Result<Vec<u8>, String> {
+    let text = text.trim().trim_end_matches('=');
+    if text.len() % 4 == 1 {
+        return Err("base64 text has the wrong length".to_string());
+    }
+
+    let mut bytes = Vec::with_capacity(text.len() / 4 * 3 + 2);
+    for chunk in text.as_bytes().chunks(4) {
+        let mut group = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            let sextet = ALPHABET
+                .iter()
+                .position(|&a| a == c)
+                .ok_or_else(|| format!("invalid character '{}' in base64 text", c as char))?;
+            group |= (sextet as u32) << (18 - 6 * i);
+        }
+
+        for i in 0..chunk.len() - 1 {
+            bytes.push((group >> (16 - 8 * i)) as u8);
+        }
+    }
+
+    Ok(bytes)
+}
@@ -0,0 +1,155 @@
+use std::iter::FromIterator;
+
+use crate::Cell;
+
+const BITS: usize = 64;
+
+/// A fixed number of bits packed into 64-bit words, one bit per cell.
+///
+/// Bits past `len` in the last word are always zero.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixedBitSet {
+    data: Vec<u64>,
+    length: usize,
+}
+
+impl FixedBitSet {
+    /// Create a set of `bits` bits, all cleared.
+    pub fn with_capacity(bits: usize) -> FixedBitSet {
+        FixedBitSet {
+            data: vec![0; bits.div_ceil(BITS)],
+            length: bits,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn contains(&self, bit: usize) -> bool {
+        debug_assert!(bit < self.length);
+        self.data[bit / BITS] & (1 << (bit % BITS)) != 0
+    }
+
+    pub fn set(&mut self, bit: usize, enabled: bool) {
+        debug_assert!(bit < self.length);
+        let mask = 1 << (bit % BITS);
+        if enabled {
+            self.data[bit / BITS] |= mask;
+        } else {
+            self.data[bit / BITS] &= !mask;
+        }
+    }
+
+    pub fn toggle(&mut self, bit: usize) {
+        debug_assert!(bit < self.length);
+        self.data[bit / BITS] ^= 1 << (bit % BITS);
+    }
+
+    /// Clear every bit, keeping the allocation.
+    pub fn clear(&mut self) {
+        for word in self.data.iter_mut() {
+            *word = 0;
+        }
+    }
+
+    /// Flip every bit.
+    pub fn invert(&mut self) {
+        for word in self.data.iter_mut() {
+            *word = !*word;
+        }
+
+        // Keep the unused bits of the last word clear.
+        let spare = self.data.len() * BITS - self.length;
+        if let Some(last) = self.data.last_mut() {
+            *last &= u64::MAX >> spare;
+        }
+    }
+
+    /// The number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.data
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// The indices of the set bits in ascending order, skipping over empty
+    /// words.
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.data.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * BITS + bit)
+            })
+        })
+    }
+
+    /// Pack the bits into `ceil(len / 8)` bytes, least significant bit first.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self
+            .data
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .collect();
+        bytes.truncate(self.length.div_ceil(8));
+        bytes
+    }
+
+    /// Unpack `bits` bits from bytes laid out as by `to_bytes`, or `None` if
+    /// `bytes` has the wrong length.
+    pub fn from_bytes(bytes: &[u8], bits: usize) -> Option<FixedBitSet> {
+        if bytes.len() != bits.div_ceil(8) {
+            return None;
+        }
+
+        let mut set = FixedBitSet::with_capacity(bits);
+        for (word, chunk) in set.data.iter_mut().zip(bytes.chunks(8)) {
+            let mut word_bytes = [0; 8];
+            word_bytes[..chunk.len()].copy_from_slice(chunk);
+            *word = u64::from_le_bytes(word_bytes);
+        }
+
+        // Keep the unused bits of the last word clear.
+        let spare = set.data.len() * BITS - bits;
+        if let Some(last) = set.data.last_mut() {
+            *last &= u64::MAX >> spare;
+        }
+
+        Some(set)
+    }
+
+    /// A 64-bit FNV-1a hash of the packed bits.
+    pub fn hash(&self) -> u64 {
+        self.data
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+
+    /// The backing words, least significant bit first.
+    pub fn as_slice(&self) -> &[u64] {
+        &self.data
+    }
+}
+
+impl FromIterator<Cell> for FixedBitSet {
+    fn from_iter<I: IntoIterator<Item = Cell>>(cells: I) -> FixedBitSet {
+        let mut set = FixedBitSet::with_capacity(0);
+        for cell in cells {
+            if set.length.is_multiple_of(BITS) {
+                set.data.push(0);
+            }
+            set.length += 1;
+            set.set(set.length - 1, cell == Cell::Alive);
+        }
+        set
+    }
+}
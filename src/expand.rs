@@ -0,0 +1,93 @@
+use crate::bitset::FixedBitSet;
+use crate::{cell_index, check_size, Universe};
+
+/// How many rows or columns `Universe::expand_to_fit` adds past an edge.
+const EXPAND_MARGIN: u32 = 16;
+
+/// Copy the per-cell `values` into a grid of `len` cells, moving the value
+/// at each index to `moved(idx)` and leaving the rest at their default.
+fn move_values<T: Copy + Default + PartialEq>(
+    values: &[T],
+    len: usize,
+    moved: impl Fn(usize) -> usize,
+) -> Vec<T> {
+    let mut moved_values = vec![T::default(); len];
+    for (idx, &value) in values.iter().enumerate() {
+        if value != T::default() {
+            moved_values[moved(idx)] = value;
+        }
+    }
+    moved_values
+}
+
+impl Universe {
+    /// Grow the universe by `EXPAND_MARGIN` rows or columns past every edge
+    /// a live cell touches, moving the cells so they keep their place
+    /// relative to each other. Their ages, dying states and species move
+    /// with them, as do the generations kept for `step_back`. Does nothing
+    /// if the grown universe would be larger than `check_size` allows,
+    /// leaving the edges to the boundary mode from then on.
+    pub(crate) fn expand_to_fit(&mut self) {
+        let (min_row, min_col, max_row, max_col) = match self.live_bounds() {
+            Some(bounds) => bounds,
+            None => return,
+        };
+
+        let margin = |touches: bool| if touches { EXPAND_MARGIN } else { 0 };
+        let top = margin(min_row == 0);
+        let bottom = margin(max_row == self.height - 1);
+        let left = margin(min_col == 0);
+        let right = margin(max_col == self.width - 1);
+        if top + bottom + left + right == 0 {
+            return;
+        }
+
+        let (new_height, new_width) = match (
+            self.height.checked_add(top + bottom),
+            self.width.checked_add(left + right),
+        ) {
+            (Some(new_height), Some(new_width)) => (new_height, new_width),
+            _ => return,
+        };
+        if check_size(new_height, new_width).is_err() {
+            return;
+        }
+
+        let width = self.width;
+        let len = new_height as usize * new_width as usize;
+        // Where cell `idx` of the current universe ends up.
+        let moved = |idx: usize| {
+            cell_index(
+                new_width,
+                idx as u32 / width + top,
+                idx as u32 % width + left,
+            )
+        };
+        let move_cells = |cells: &FixedBitSet| {
+            let mut moved_cells = FixedBitSet::with_capacity(len);
+            for idx in cells.ones() {
+                moved_cells.set(moved(idx), true);
+            }
+            moved_cells
+        };
+
+        let cells = move_cells(&self.cells);
+        let ages = move_values(&self.ages, len, moved);
+        let dying = move_values(&self.dying, len, moved);
+        let species = move_values(&self.species, len, moved);
+        let history = self.history.iter().map(move_cells).collect();
+
+        self.width = new_width;
+        self.height = new_height;
+        self.replace_cells(cells);
+        self.ages = ages;
+        self.dying = dying;
+        self.species = species;
+        self.history = history;
+        self.changed_cells.clear();
+        if let Some((row, column)) = self.watched_cell.as_mut() {
+            *row += top;
+            *column += left;
+        }
+    }
+}
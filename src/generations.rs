@@ -0,0 +1,38 @@
+use crate::Universe;
+
+impl Universe {
+    /// Move every dying cell one state further along the decay chain, and
+    /// start the cells that just died on it.
+    ///
+    /// In the Generations rules a live cell that doesn't survive passes
+    /// through `states - 2` dying states before it is dead. Dying cells don't
+    /// count as live neighbors and can't be born, so `step_once` treats them
+    /// as dead apart from that. Every cell that moves along the chain is
+    /// added to `changed_cells`, since it needs to be redrawn.
+    pub(crate) fn decay(&mut self) {
+        if self.states <= 2 {
+            return;
+        }
+
+        let died: Vec<usize> = self
+            .changed_cells
+            .iter()
+            .map(|&idx| idx as usize)
+            .filter(|&idx| !self.cells.contains(idx))
+            .collect();
+
+        let last = self.states - 1;
+        for (idx, state) in self.dying.iter_mut().enumerate() {
+            if *state != 0 {
+                *state = if *state == last { 0 } else { *state + 1 };
+                self.changed_cells.push(idx as u32);
+            }
+        }
+
+        for idx in died {
+            self.dying[idx] = 2;
+        }
+
+        self.changed_cells.sort_unstable();
+    }
+}
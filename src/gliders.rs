@@ -0,0 +1,217 @@
+use crate::bitset::FixedBitSet;
+use crate::{clip_coord, wrap_coord, BoundaryMode, Pattern, Universe};
+
+/// How many generations a glider can go unrecognized, for example while
+/// passing close to something, and still be recognized as the same glider.
+const TRACKING_GAP: u64 = 12;
+
+/// One of the 3 by 3 bounding boxes of a glider, with bit `row * 3 + col`
+/// set for every live cell.
+type Shape = u16;
+
+/// A glider seen while running `Universe::count_escaping_gliders`.
+struct Sighting {
+    row: u32,
+    column: u32,
+    generation: u64,
+}
+
+/// The cells of a pattern one generation later under Conway's rules, on an
+/// unbounded grid.
+fn step_conway(cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    let live_neighbors = |row: i32, col: i32| {
+        cells
+            .iter()
+            .filter(|&&(r, c)| (r, c) != (row, col) && (r - row).abs() <= 1 && (c - col).abs() <= 1)
+            .count()
+    };
+
+    let mut next: Vec<(i32, i32)> = cells
+        .iter()
+        .flat_map(|&(row, col)| {
+            (-1..=1).flat_map(move |d_row| (-1..=1).map(move |d_col| (row + d_row, col + d_col)))
+        })
+        .filter(|&(row, col)| match live_neighbors(row, col) {
+            3 => true,
+            2 => cells.contains(&(row, col)),
+            _ => false,
+        })
+        .collect();
+    next.sort_unstable();
+    next.dedup();
+    next
+}
+
+/// Every phase of a glider travelling in every direction.
+fn glider_shapes() -> Vec<Shape> {
+    let mut shapes = Vec::new();
+    let mut cells = Pattern::Glider.offsets().to_vec();
+
+    for _ in 0..4 {
+        let min_row = cells.iter().map(|&(row, _)| row).min().unwrap_or(0);
+        let min_col = cells.iter().map(|&(_, col)| col).min().unwrap_or(0);
+
+        for symmetry in 0..8 {
+            let shape = cells.iter().fold(0, |shape, &(row, col)| {
+                let (mut row, mut col) = (row - min_row, col - min_col);
+                if symmetry & 1 != 0 {
+                    std::mem::swap(&mut row, &mut col);
+                }
+                if symmetry & 2 != 0 {
+                    row = 2 - row;
+                }
+                if symmetry & 4 != 0 {
+                    col = 2 - col;
+                }
+                shape | 1 << (row * 3 + col)
+            });
+            if !shapes.contains(&shape) {
+                shapes.push(shape);
+            }
+        }
+
+        cells = step_conway(&cells);
+    }
+
+    shapes
+}
+
+impl Universe {
+    /// Move `coord` by `offset` along an axis with the given boundary mode,
+    /// or `None` if it falls off an edge that doesn't wrap around.
+    fn move_along(mode: BoundaryMode, coord: u32, offset: i32, size: u32) -> Option<u32> {
+        match mode {
+            BoundaryMode::Toroidal => Some(wrap_coord(coord, offset, size)),
+            BoundaryMode::Dead | BoundaryMode::Mirror => clip_coord(coord, offset, size),
+        }
+    }
+
+    fn offset_index(&self, row: u32, column: u32, d_row: i32, d_col: i32) -> Option<usize> {
+        let row = Universe::move_along(self.boundaries.y, row, d_row, self.height)?;
+        let column = Universe::move_along(self.boundaries.x, column, d_col, self.width)?;
+        Some(self.get_index(row, column))
+    }
+
+    /// The top-left corner of every glider with nothing else alive within
+    /// two cells of it.
+    fn isolated_gliders(&self, shapes: &[Shape]) -> Vec<(u32, u32)> {
+        let mut seen = FixedBitSet::with_capacity(self.cells.len());
+        let mut gliders = Vec::new();
+
+        for start in self.cells.ones() {
+            if seen.contains(start) {
+                continue;
+            }
+
+            // Flood fill the group of touching cells, keeping their offsets
+            // from the first one so that groups across a wrapping edge stay
+            // in one piece. Groups larger than a glider are still filled so
+            // they aren't visited again.
+            let start_row = start as u32 / self.width;
+            let start_col = start as u32 % self.width;
+            seen.set(start, true);
+            let mut stack = vec![(start_row, start_col, 0, 0)];
+            let mut offsets = Vec::new();
+            while let Some((row, column, d_row, d_col)) = stack.pop() {
+                offsets.push((d_row, d_col));
+                for n_row in -1..=1 {
+                    for n_col in -1..=1 {
+                        let idx = match self.offset_index(row, column, n_row, n_col) {
+                            Some(idx) => idx,
+                            None => continue,
+                        };
+                        if self.cells.contains(idx) && !seen.contains(idx) {
+                            seen.set(idx, true);
+                            let (row, column) = (idx as u32 / self.width, idx as u32 % self.width);
+                            stack.push((row, column, d_row + n_row, d_col + n_col));
+                        }
+                    }
+                }
+            }
+
+            if offsets.len() != 5 {
+                continue;
+            }
+            let min_row = offsets.iter().map(|&(row, _)| row).min().unwrap_or(0);
+            let min_col = offsets.iter().map(|&(_, col)| col).min().unwrap_or(0);
+            if offsets
+                .iter()
+                .any(|&(row, col)| row - min_row > 2 || col - min_col > 2)
+            {
+                continue;
+            }
+            let shape = offsets.iter().fold(0, |shape: Shape, &(row, col)| {
+                shape | 1 << ((row - min_row) * 3 + col - min_col)
+            });
+            if !shapes.contains(&shape) {
+                continue;
+            }
+
+            // Nothing else may be alive in the ring two cells out from the
+            // bounding box; the ring one cell out is empty already, or it
+            // would have been part of the group.
+            let top = Universe::move_along(self.boundaries.y, start_row, min_row, self.height);
+            let left = Universe::move_along(self.boundaries.x, start_col, min_col, self.width);
+            let (top, left) = match (top, left) {
+                (Some(top), Some(left)) => (top, left),
+                _ => continue,
+            };
+            let isolated = (-2..=4)
+                .flat_map(|d_row| (-2..=4).map(move |d_col| (d_row, d_col)))
+                .filter(|&(d_row, d_col)| d_row.min(d_col) == -2 || d_row.max(d_col) == 4)
+                .filter_map(|(d_row, d_col)| self.offset_index(top, left, d_row, d_col))
+                .all(|idx| !self.cells.contains(idx));
+            if isolated {
+                gliders.push((top, left));
+            }
+        }
+
+        gliders
+    }
+
+    /// Run `gens` generations and count the gliders that fly off on their
+    /// own. See `Universe::escaping_gliders` for how they are recognized.
+    pub(crate) fn count_escaping_gliders(&mut self, gens: u32) -> u32 {
+        let shapes = glider_shapes();
+        let mut sightings: Vec<Sighting> = Vec::new();
+        let mut count = 0;
+        // The distance between two coordinates, the short way around.
+        let distance = |a: u32, b: u32, size: u32| {
+            let d = a.abs_diff(b);
+            d.min(size - d)
+        };
+
+        for _ in 0..gens {
+            self.step_once();
+
+            sightings.retain(|sighting| self.generation - sighting.generation <= TRACKING_GAP);
+            for (row, column) in self.isolated_gliders(&shapes) {
+                let generation = self.generation;
+                // A glider moves one cell diagonally every four generations,
+                // and its bounding box at most one cell further.
+                let known = sightings.iter_mut().find(|sighting| {
+                    let reach = (generation - sighting.generation) as u32 / 4 + 1;
+                    distance(sighting.row, row, self.height) <= reach
+                        && distance(sighting.column, column, self.width) <= reach
+                });
+                match known {
+                    Some(sighting) => {
+                        sighting.row = row;
+                        sighting.column = column;
+                        sighting.generation = generation;
+                    }
+                    None => {
+                        count += 1;
+                        sightings.push(Sighting {
+                            row,
+                            column,
+                            generation,
+                        });
+                    }
+                }
+            }
+        }
+
+        count
+    }
+}
@@ -0,0 +1,435 @@
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+
+use crate::bitset::FixedBitSet;
+use crate::rule::Rule;
+use crate::{Boundaries, BoundaryMode, Cell, Neighborhood, Universe};
+
+type NodeId = u32;
+
+/// The two leaves, a single dead or alive cell.
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+
+/// Once this many nodes have been created the cache is dropped and rebuilt
+/// from scratch, so memory use stays bounded.
+const MAX_NODES: usize = 1 << 22;
+
+/// A multiply-rotate hasher for the small integer keys of the caches, which
+/// are hashed far too often for the default DoS-resistant hasher.
+#[derive(Default)]
+struct NodeHasher(u64);
+
+impl Hasher for NodeHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.write_u64(byte as u64);
+        }
+    }
+
+    fn write_u8(&mut self, n: u8) {
+        self.write_u64(n as u64);
+    }
+
+    fn write_u32(&mut self, n: u32) {
+        self.write_u64(n as u64);
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.0 = (self.0.rotate_left(5) ^ n).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+type NodeMap<K> = HashMap<K, NodeId, BuildHasherDefault<NodeHasher>>;
+
+/// A square block of `2^level` by `2^level` cells.
+#[derive(Clone, Copy)]
+struct Node {
+    level: u8,
+    /// The north-west, north-east, south-west and south-east quadrants.
+    /// Unused for leaves.
+    children: [NodeId; 4],
+    population: u64,
+}
+
+/// A memoized quadtree of blocks, after Gosper's HashLife.
+///
+/// Every distinct block is stored once, and the result of advancing a block
+/// is cached, so patterns made of repeated blocks are advanced without
+/// looking at their cells again.
+pub struct HashLife {
+    rule: Rule,
+    nodes: Vec<Node>,
+    /// The node for each combination of quadrants.
+    index: NodeMap<[NodeId; 4]>,
+    /// The center of a node advanced by `2^j` generations, keyed by the node
+    /// and `j`.
+    results: NodeMap<(NodeId, u8)>,
+    /// The empty node of every level built so far.
+    empty: Vec<NodeId>,
+    /// Every 2x2 node, indexed by its cells as bits in quadrant order, so
+    /// building a universe doesn't need a lookup per pair of rows.
+    level_one: [NodeId; 16],
+}
+
+impl HashLife {
+    pub fn new(rule: Rule) -> HashLife {
+        let mut hashlife = HashLife {
+            rule,
+            nodes: Vec::new(),
+            index: NodeMap::default(),
+            results: NodeMap::default(),
+            empty: Vec::new(),
+            level_one: [DEAD; 16],
+        };
+        hashlife.reset();
+        hashlife
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Forget every node except the two leaves.
+    fn reset(&mut self) {
+        let leaf = |population| Node {
+            level: 0,
+            children: [DEAD; 4],
+            population,
+        };
+
+        self.nodes.clear();
+        self.nodes.push(leaf(0));
+        self.nodes.push(leaf(1));
+        self.index.clear();
+        self.results.clear();
+        self.empty = vec![DEAD];
+
+        for bits in 0..16 {
+            let leaf = |quadrant: usize| {
+                if bits & (1 << quadrant) != 0 {
+                    ALIVE
+                } else {
+                    DEAD
+                }
+            };
+            self.level_one[bits] = self.join([leaf(0), leaf(1), leaf(2), leaf(3)]);
+        }
+    }
+
+    fn node(&self, id: NodeId) -> Node {
+        self.nodes[id as usize]
+    }
+
+    /// The `quadrant`th quadrant of a node.
+    fn child(&self, id: NodeId, quadrant: usize) -> NodeId {
+        self.node(id).children[quadrant]
+    }
+
+    /// The node made of the given quadrants, creating it if it's new.
+    fn join(&mut self, children: [NodeId; 4]) -> NodeId {
+        if let Some(&id) = self.index.get(&children) {
+            return id;
+        }
+
+        let level = self.node(children[0]).level + 1;
+        let population = children.iter().map(|&id| self.node(id).population).sum();
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node {
+            level,
+            children,
+            population,
+        });
+        self.index.insert(children, id);
+        id
+    }
+
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let empty = *self.empty.last().expect("the dead leaf is always there");
+            let empty = self.join([empty; 4]);
+            self.empty.push(empty);
+        }
+        self.empty[level as usize]
+    }
+
+    /// The center half of a node, without advancing it.
+    fn center(&mut self, id: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.node(id).children;
+        self.join([
+            self.child(nw, 3),
+            self.child(ne, 2),
+            self.child(sw, 1),
+            self.child(se, 0),
+        ])
+    }
+
+    /// Advance the center 2x2 cells of a 4x4 node by one generation.
+    fn step_leaves(&mut self, id: NodeId) -> NodeId {
+        let mut grid = [[false; 4]; 4];
+        for (quadrant, &child) in self.node(id).children.iter().enumerate() {
+            for (i, &leaf) in self.node(child).children.iter().enumerate() {
+                let row = quadrant / 2 * 2 + i / 2;
+                let col = quadrant % 2 * 2 + i % 2;
+                grid[row][col] = leaf == ALIVE;
+            }
+        }
+
+        let mut next = [DEAD; 4];
+        for (i, next) in next.iter_mut().enumerate() {
+            let (row, col) = (1 + i / 2, 1 + i % 2);
+            let mut live_neighbors = 0;
+            for (neighbor_row, grid_row) in grid.iter().enumerate().skip(row - 1).take(3) {
+                for (neighbor_col, &alive) in grid_row.iter().enumerate().skip(col - 1).take(3) {
+                    if (neighbor_row, neighbor_col) != (row, col) {
+                        live_neighbors += alive as u8;
+                    }
+                }
+            }
+
+            let cell = Cell::from(grid[row][col]);
+            if self.rule.next_cell(cell, live_neighbors) == Cell::Alive {
+                *next = ALIVE;
+            }
+        }
+
+        self.join(next)
+    }
+
+    /// The center half of a node of level `k` advanced by `2^j` generations,
+    /// where `j <= k - 2`.
+    fn successor(&mut self, id: NodeId, j: u8) -> NodeId {
+        let node = self.node(id);
+        debug_assert!(node.level >= 2 && j <= node.level - 2);
+
+        if node.population == 0 {
+            return self.empty(node.level - 1);
+        }
+        if let Some(&result) = self.results.get(&(id, j)) {
+            return result;
+        }
+
+        let result = if node.level == 2 {
+            self.step_leaves(id)
+        } else {
+            let [nw, ne, sw, se] = node.children;
+
+            // The nine overlapping blocks of half the size covering the node.
+            let n01 = self.join([
+                self.child(nw, 1),
+                self.child(ne, 0),
+                self.child(nw, 3),
+                self.child(ne, 2),
+            ]);
+            let n10 = self.join([
+                self.child(nw, 2),
+                self.child(nw, 3),
+                self.child(sw, 0),
+                self.child(sw, 1),
+            ]);
+            let n11 = self.join([
+                self.child(nw, 3),
+                self.child(ne, 2),
+                self.child(sw, 1),
+                self.child(se, 0),
+            ]);
+            let n12 = self.join([
+                self.child(ne, 2),
+                self.child(ne, 3),
+                self.child(se, 0),
+                self.child(se, 1),
+            ]);
+            let n21 = self.join([
+                self.child(sw, 1),
+                self.child(se, 0),
+                self.child(sw, 3),
+                self.child(se, 2),
+            ]);
+            let blocks = [nw, n01, ne, n10, n11, n12, sw, n21, se];
+
+            // At full speed both halves advance by `2^(k - 3)` generations,
+            // otherwise only the second half advances by `2^j`.
+            let full_speed = j == node.level - 2;
+            let mut r = [DEAD; 9];
+            for (r, &block) in r.iter_mut().zip(blocks.iter()) {
+                *r = if full_speed {
+                    self.successor(block, j - 1)
+                } else {
+                    self.center(block)
+                };
+            }
+            let j = if full_speed { j - 1 } else { j };
+
+            let quadrants = [
+                self.join([r[0], r[1], r[3], r[4]]),
+                self.join([r[1], r[2], r[4], r[5]]),
+                self.join([r[3], r[4], r[6], r[7]]),
+                self.join([r[4], r[5], r[7], r[8]]),
+            ];
+            let mut result = [DEAD; 4];
+            for (result, &quadrant) in result.iter_mut().zip(quadrants.iter()) {
+                *result = self.successor(quadrant, j);
+            }
+            self.join(result)
+        };
+
+        self.results.insert((id, j), result);
+        result
+    }
+
+    /// Build the node of the given level whose top-left cell is at `top` and
+    /// `left` in the infinite plane tiled with copies of `cells`.
+    fn build_tiled(&mut self, tile: &Tile, level: u8, top: u64, left: u64) -> NodeId {
+        if level == 1 {
+            let bits = tile.contains(top, left) as usize
+                | (tile.contains(top, left + 1) as usize) << 1
+                | (tile.contains(top + 1, left) as usize) << 2
+                | (tile.contains(top + 1, left + 1) as usize) << 3;
+            return self.level_one[bits];
+        }
+
+        let half = 1 << (level - 1);
+        let children = [
+            self.build_tiled(tile, level - 1, top, left),
+            self.build_tiled(tile, level - 1, top, left + half),
+            self.build_tiled(tile, level - 1, top + half, left),
+            self.build_tiled(tile, level - 1, top + half, left + half),
+        ];
+        self.join(children)
+    }
+
+    /// Set the live cells of a node whose top-left cell is at `top` and
+    /// `left` in `out`, dropping those past `height` and `width`.
+    fn write_cells(&self, id: NodeId, top: u64, left: u64, out: &mut FixedBitSet, tile: &Tile) {
+        let node = self.node(id);
+        if node.population == 0 || top >= tile.height || left >= tile.width {
+            return;
+        }
+
+        if node.level == 0 {
+            out.set((top * tile.width + left) as usize, true);
+            return;
+        }
+
+        let half = 1 << (node.level - 1);
+        let [nw, ne, sw, se] = node.children;
+        self.write_cells(nw, top, left, out, tile);
+        self.write_cells(ne, top, left + half, out, tile);
+        self.write_cells(sw, top + half, left, out, tile);
+        self.write_cells(se, top + half, left + half, out, tile);
+    }
+
+    /// Advance a toroidal `height` by `width` universe by up to `max_steps`
+    /// generations, returning the new cells and how many generations they
+    /// were advanced by.
+    ///
+    /// The torus evolves exactly like the infinite plane tiled with copies
+    /// of it. A root node twice the size of the universe is filled with
+    /// that tiling and advanced by a power of two no larger than a quarter
+    /// of its size, so nothing from beyond its edges can reach the center
+    /// half the universe is read back from.
+    pub fn advance(
+        &mut self,
+        cells: &FixedBitSet,
+        height: u32,
+        width: u32,
+        max_steps: u32,
+    ) -> (FixedBitSet, u32) {
+        if self.nodes.len() > MAX_NODES {
+            self.reset();
+        }
+
+        let level = (2 * height.max(width))
+            .next_power_of_two()
+            .trailing_zeros()
+            .max(2) as u8;
+        let j = (31 - max_steps.leading_zeros() as u8).min(level - 2);
+
+        // Offset the tiling so the result, the center half of the root,
+        // starts with the top-left cell of the universe.
+        let offset = 1u64 << (level - 2);
+        let tile = Tile {
+            cells,
+            height: height as u64,
+            width: width as u64,
+            offset,
+        };
+
+        let root = self.build_tiled(&tile, level, 0, 0);
+        let result = self.successor(root, j);
+
+        let mut next = FixedBitSet::with_capacity(cells.len());
+        self.write_cells(result, 0, 0, &mut next, &tile);
+        (next, 1 << j)
+    }
+}
+
+/// A universe tiled across the plane, shifted by `offset` cells in both
+/// directions.
+struct Tile<'a> {
+    cells: &'a FixedBitSet,
+    height: u64,
+    width: u64,
+    offset: u64,
+}
+
+impl<'a> Tile<'a> {
+    fn contains(&self, row: u64, column: u64) -> bool {
+        let row = (row + self.height - self.offset % self.height) % self.height;
+        let column = (column + self.width - self.offset % self.width) % self.width;
+        self.cells.contains((row * self.width + column) as usize)
+    }
+}
+
+impl Universe {
+    /// Advance the universe by `steps` generations with HashLife.
+    ///
+    /// Only plain toroidal universes with the Moore neighborhood and a rule
+    /// that doesn't bring isolated dead cells to life can be advanced this
+    /// way; anything else falls back to `step_once`. The cache is kept between
+    /// calls and rebuilt whenever the rule changes.
+    pub(crate) fn step_hashed(&mut self, steps: u32) {
+        if self.boundaries != Boundaries::uniform(BoundaryMode::Toroidal)
+            || self.neighborhood != Neighborhood::Moore
+            || self.rule.next_cell(Cell::Dead, 0) == Cell::Alive
+            || !self.is_plain()
+        {
+            for _ in 0..steps {
+                self.step_once();
+            }
+            return;
+        }
+
+        let mut hashlife = match self.hashlife.take() {
+            Some(hashlife) if hashlife.rule() == self.rule => hashlife,
+            _ => HashLife::new(self.rule),
+        };
+
+        let mut cells = self.cells.clone();
+        let mut remaining = steps;
+        while remaining > 0 {
+            let (next, advanced) = hashlife.advance(&cells, self.height, self.width, remaining);
+            cells = next;
+            remaining -= advanced;
+        }
+        self.hashlife = Some(hashlife);
+
+        self.changed_cells = (0..cells.len())
+            .filter(|&idx| cells.contains(idx) != self.cells.contains(idx))
+            .map(|idx| idx as u32)
+            .collect();
+        self.activity = self.changed_cells.len() as u32;
+        self.replace_cells(cells);
+        self.generation += steps as u64;
+        self.history.clear();
+        self.stable = false;
+        if steps > 0 {
+            self.record_population();
+            self.record_watched_cell();
+        }
+    }
+}
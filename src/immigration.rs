@@ -0,0 +1,69 @@
+use crate::bitset::FixedBitSet;
+use crate::Universe;
+
+/// The species every live cell starts out as.
+pub const FIRST_SPECIES: u8 = 1;
+/// The other species of the Immigration Game.
+pub const SECOND_SPECIES: u8 = 2;
+
+/// The species of every cell in `cells`: the first species for the live
+/// ones and 0 for the dead ones.
+pub fn initial_species(cells: &FixedBitSet) -> Vec<u8> {
+    (0..cells.len())
+        .map(|idx| cells.contains(idx) as u8 * FIRST_SPECIES)
+        .collect()
+}
+
+impl Universe {
+    /// Bring the species up to date once `buffer_cells` holds the next
+    /// generation and `changed_cells` the cells that differ from `cells`.
+    ///
+    /// In the Immigration Game survivors keep their species, and a cell that
+    /// is born takes the species of the majority of its live neighbors, the
+    /// first species on a tie. The rule and neighbor counting are otherwise
+    /// the same as with a single species.
+    pub(crate) fn update_species(&mut self) {
+        let births: Vec<(usize, u8)> = self
+            .changed_cells
+            .iter()
+            .map(|&idx| idx as usize)
+            .filter(|&idx| self.buffer_cells.contains(idx))
+            .map(|idx| (idx, self.majority_species(idx)))
+            .collect();
+
+        for &idx in &self.changed_cells {
+            self.species[idx as usize] = 0;
+        }
+        for (idx, species) in births {
+            self.species[idx] = species;
+        }
+    }
+
+    /// The species most of the live neighbors of cell `idx` belong to.
+    fn majority_species(&self, idx: usize) -> u8 {
+        let neighbors: Vec<usize> = if self.range > 1 {
+            self.range_neighbors(idx)
+        } else {
+            self.neighbor_indices(idx)
+                .iter()
+                .flatten()
+                .copied()
+                .collect()
+        };
+
+        let second = neighbors
+            .iter()
+            .filter(|&&neighbor| self.species[neighbor] == SECOND_SPECIES)
+            .count();
+        let first = neighbors
+            .iter()
+            .filter(|&&neighbor| self.species[neighbor] == FIRST_SPECIES)
+            .count();
+
+        if second > first {
+            SECOND_SPECIES
+        } else {
+            FIRST_SPECIES
+        }
+    }
+}
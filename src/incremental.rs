@@ -0,0 +1,154 @@
+use crate::bitset::FixedBitSet;
+use crate::rule::Rule;
+use crate::{Boundaries, BoundaryMode, Cell, Neighborhood, Universe};
+
+/// Live neighbor counts for every cell, kept up to date between calls to
+/// `Universe::step_incremental`.
+pub struct NeighborCounts {
+    counts: Vec<u8>,
+    /// The cells, size, rule, boundary modes and neighborhood `counts`
+    /// belong to. If any of them changed since the last incremental step the
+    /// counts are rebuilt.
+    cells: FixedBitSet,
+    width: u32,
+    height: u32,
+    rule: Rule,
+    boundaries: Boundaries,
+    neighborhood: Neighborhood,
+}
+
+impl Universe {
+    /// The indices of the eight neighbors of a cell, or `None` for neighbors
+    /// off a dead edge and, in the von Neumann neighborhood, the diagonals.
+    pub(crate) fn neighbor_indices(&self, idx: usize) -> [Option<usize>; 8] {
+        let row = idx as u32 / self.width;
+        let column = idx as u32 % self.width;
+
+        let north = self.boundaries.y.previous(row, self.height);
+        let south = self.boundaries.y.next(row, self.height);
+        let west = self.boundaries.x.previous(column, self.width);
+        let east = self.boundaries.x.next(column, self.width);
+        let (row, column) = (Some(row), Some(column));
+
+        let index = |row: Option<u32>, column: Option<u32>| match (row, column) {
+            (Some(row), Some(column)) => Some(self.get_index(row, column)),
+            _ => None,
+        };
+        let diagonal = |row: Option<u32>, column: Option<u32>| match self.neighborhood {
+            Neighborhood::Moore => index(row, column),
+            Neighborhood::VonNeumann => None,
+        };
+
+        [
+            diagonal(north, west),
+            index(north, column),
+            diagonal(north, east),
+            index(row, west),
+            index(row, east),
+            diagonal(south, west),
+            index(south, column),
+            diagonal(south, east),
+        ]
+    }
+
+    /// Whether the cached neighbor counts still describe the universe.
+    fn neighbor_counts_up_to_date(&self) -> bool {
+        match &self.neighbor_counts {
+            Some(counts) => {
+                counts.cells == self.cells
+                    && counts.width == self.width
+                    && counts.height == self.height
+                    && counts.rule == self.rule
+                    && counts.boundaries == self.boundaries
+                    && counts.neighborhood == self.neighborhood
+            }
+            None => false,
+        }
+    }
+
+    /// Advance the universe by a single generation like `step_once`, but
+    /// only evaluate cells whose neighborhood changed in the last generation.
+    ///
+    /// A cell that didn't change and whose neighbors didn't change either
+    /// keeps its state, so only the cells that changed and their neighbors
+    /// need to be looked at. The neighbor counts are kept in a grid that is
+    /// adjusted as cells are born and die, and rebuilt from scratch whenever
+    /// the universe was edited in between. Mirrored edges don't have
+    /// symmetric neighborhoods, and dying cells and random births aren't
+    /// tracked by the counts, so those fall back to `step_once`.
+    pub(crate) fn step_incremental(&mut self) {
+        if self.boundaries.any(BoundaryMode::Mirror) || !self.is_plain() {
+            self.step_once();
+            return;
+        }
+
+        let candidates: Vec<usize> = if self.neighbor_counts_up_to_date() {
+            let mut seen = FixedBitSet::with_capacity(self.cells.len());
+            let mut candidates = Vec::new();
+            for &idx in &self.changed_cells {
+                let idx = idx as usize;
+                let neighbors = self.neighbor_indices(idx);
+                for candidate in neighbors.iter().flatten().chain(Some(&idx)) {
+                    if !seen.contains(*candidate) {
+                        seen.set(*candidate, true);
+                        candidates.push(*candidate);
+                    }
+                }
+            }
+            candidates
+        } else {
+            self.neighbor_counts = Some(NeighborCounts {
+                counts: self.neighbor_counts(),
+                cells: self.cells.clone(),
+                width: self.width,
+                height: self.height,
+                rule: self.rule,
+                boundaries: self.boundaries,
+                neighborhood: self.neighborhood,
+            });
+            (0..self.cells.len()).collect()
+        };
+
+        self.push_history();
+
+        let mut neighbor_counts = self
+            .neighbor_counts
+            .take()
+            .expect("neighbor counts were just brought up to date");
+
+        let mut changes: Vec<usize> = candidates
+            .into_iter()
+            .filter(|&idx| {
+                let cell = Cell::from(self.cells.contains(idx));
+                self.rule.next_cell(cell, neighbor_counts.counts[idx]) != cell
+            })
+            .collect();
+        changes.sort_unstable();
+
+        self.changed_cells.clear();
+        for idx in changes {
+            self.cells.toggle(idx);
+            self.changed_cells.push(idx as u32);
+
+            let born = self.cells.contains(idx);
+            if born {
+                self.live_count += 1;
+            } else {
+                self.live_count -= 1;
+            }
+
+            for neighbor in self.neighbor_indices(idx).iter().flatten() {
+                if born {
+                    neighbor_counts.counts[*neighbor] += 1;
+                } else {
+                    neighbor_counts.counts[*neighbor] -= 1;
+                }
+            }
+        }
+
+        neighbor_counts.cells = self.cells.clone();
+        self.neighbor_counts = Some(neighbor_counts);
+
+        self.finish_step();
+    }
+}
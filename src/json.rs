@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/// A universe as written by `Universe::to_json`, with one 0 or 1 per cell in
+/// row-major order.
+#[derive(Serialize, Deserialize)]
+pub struct Snapshot {
+    pub width: u32,
+    pub height: u32,
+    pub generation: u64,
+    pub cells: Vec<u8>,
+}
+
+pub fn encode(snapshot: &Snapshot) -> String {
+    serde_json::to_string(snapshot).expect("a snapshot is always valid JSON")
+}
+
+/// Parse a snapshot, checking that it holds exactly one 0 or 1 for every
+/// cell.
+pub fn decode(json: &str) -> Result<Snapshot, String> {
+    let snapshot: Snapshot =
+        serde_json::from_str(json).map_err(|err| format!("invalid universe JSON: {}", err))?;
+
+    let cell_count = snapshot.width as usize * snapshot.height as usize;
+    if snapshot.cells.len() != cell_count {
+        return Err(format!(
+            "universe JSON has {} cells, expected {} for {}x{}",
+            snapshot.cells.len(),
+            cell_count,
+            snapshot.height,
+            snapshot.width
+        ));
+    }
+    if let Some(cell) = snapshot.cells.iter().find(|&&cell| cell > 1) {
+        return Err(format!("invalid cell {} in universe JSON", cell));
+    }
+
+    Ok(snapshot)
+}
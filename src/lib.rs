@@ -1,7 +1,36 @@
+mod base64;
+mod bitset;
+mod expand;
+mod generations;
+mod gliders;
+mod hashlife;
+mod immigration;
+mod incremental;
+mod json;
+mod ltl;
+mod lut;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod pattern;
+mod plaintext;
+mod predecessor;
+mod rle;
+mod rng;
+mod rule;
 mod utils;
 
-use js_sys;
+use bitset::FixedBitSet;
+use hashlife::HashLife;
+use incremental::NeighborCounts;
+use ltl::RangeRule;
+pub use pattern::{GliderDirection, Pattern};
+use rng::Rng;
+use rule::Rule;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::convert::TryInto;
 use std::fmt;
+use std::sync::atomic::{AtomicU32, Ordering};
 use wasm_bindgen::prelude::*;
 use web_sys::console;
 
@@ -12,6 +41,7 @@ use web_sys::console;
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
 // A macro to provide `println!(..)`-style syntax for `console.log` logging.
+#[allow(unused_macros)]
 macro_rules! log {
     ( $( $t:tt )* ) => {
         web_sys::console::log_1(&format!( $( $t )* ).into());
@@ -35,6 +65,26 @@ impl<'a> Drop for Timer<'a> {
     }
 }
 
+/// Magic bytes at the start of a serialized universe.
+const SERIALIZED_MAGIC: &[u8; 4] = b"GOL\0";
+/// Version of the serialized universe format.
+const SERIALIZED_VERSION: u8 = 1;
+/// Length of the serialized header: magic, version, width, height and
+/// generation.
+const SERIALIZED_HEADER_LEN: usize = 4 + 1 + 4 + 4 + 8;
+
+/// The most characters a line of a PBM image may have.
+const PBM_LINE_LEN: usize = 70;
+
+/// The largest number of cells `check_size` allows, set with
+/// `set_max_cells`.
+static MAX_CELLS: AtomicU32 = AtomicU32::new(DEFAULT_MAX_CELLS);
+/// The default for `MAX_CELLS`.
+const DEFAULT_MAX_CELLS: u32 = 1 << 26;
+
+/// How many generations `run_until` runs between progress reports.
+const PROGRESS_INTERVAL: u32 = 64;
+
 #[wasm_bindgen]
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -43,358 +93,2586 @@ pub enum Cell {
     Alive = 1,
 }
 
-impl Cell {
-    fn toggle(&mut self) {
-        *self = match *self {
-            Cell::Dead => Cell::Alive,
-            Cell::Alive => Cell::Dead,
+impl From<bool> for Cell {
+    fn from(alive: bool) -> Cell {
+        if alive {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+}
+
+/// How neighbors are found for cells on the edge of the universe.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoundaryMode {
+    /// Edges wrap around to the opposite side, making the universe a torus.
+    Toroidal,
+    /// Everything outside the universe counts as dead.
+    Dead,
+    /// The edge row or column is reflected outwards.
+    Mirror,
+}
+
+/// Which cells around a cell count as its neighbors.
+///
+/// The familiar B/S rules such as Conway's `B3/S23` assume the Moore
+/// neighborhood, so they behave very differently under von Neumann.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// The eight surrounding cells, including the diagonals.
+    Moore,
+    /// Only the four orthogonally adjacent cells.
+    VonNeumann,
+}
+
+impl BoundaryMode {
+    /// The coordinate before `coord` on an axis of length `size`, or `None`
+    /// if it falls off a dead edge.
+    fn previous(self, coord: u32, size: u32) -> Option<u32> {
+        if coord > 0 {
+            return Some(coord - 1);
+        }
+
+        match self {
+            BoundaryMode::Toroidal => Some(size - 1),
+            BoundaryMode::Dead => None,
+            BoundaryMode::Mirror => Some(coord),
+        }
+    }
+
+    /// The coordinate after `coord` on an axis of length `size`, or `None`
+    /// if it falls off a dead edge.
+    fn next(self, coord: u32, size: u32) -> Option<u32> {
+        if coord < size - 1 {
+            return Some(coord + 1);
+        }
+
+        match self {
+            BoundaryMode::Toroidal => Some(0),
+            BoundaryMode::Dead => None,
+            BoundaryMode::Mirror => Some(coord),
+        }
+    }
+
+    /// The coordinate `offset` away from `coord` on an axis of length
+    /// `size`, or `None` if it falls off a dead edge. Mirrored edges reflect
+    /// it back in the way `previous` and `next` do.
+    fn offset(self, coord: u32, offset: i64, size: u32) -> Option<u32> {
+        let coord = coord as i64 + offset;
+        let size = size as i64;
+        if (0..size).contains(&coord) {
+            return Some(coord as u32);
+        }
+
+        match self {
+            BoundaryMode::Toroidal => Some(coord.rem_euclid(size) as u32),
+            BoundaryMode::Dead => None,
+            BoundaryMode::Mirror => {
+                let folded = coord.rem_euclid(2 * size);
+                Some(if folded < size {
+                    folded
+                } else {
+                    2 * size - 1 - folded
+                } as u32)
+            }
+        }
+    }
+}
+
+/// The boundary modes of the two axes, which can differ: a cylinder wraps
+/// around horizontally but has dead top and bottom edges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Boundaries {
+    /// The left and right edges.
+    x: BoundaryMode,
+    /// The top and bottom edges.
+    y: BoundaryMode,
+}
+
+impl Boundaries {
+    fn uniform(mode: BoundaryMode) -> Boundaries {
+        Boundaries { x: mode, y: mode }
+    }
+
+    /// Whether either axis uses `mode`.
+    fn any(self, mode: BoundaryMode) -> bool {
+        self.x == mode || self.y == mode
+    }
+}
+
+/// Deterministic starting configurations for `Universe::new_pattern`.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeedPattern {
+    /// Alternating cells, with the top-left cell alive.
+    Checkerboard,
+    /// Alternating rows, with the top row alive.
+    Stripes,
+    /// The middle row and the middle column.
+    Cross,
+    /// Every cell on the edge of the universe.
+    BorderRing,
+}
+
+impl SeedPattern {
+    /// The state of the cell at `row` and `column` in a `height` by `width`
+    /// universe seeded with this pattern.
+    fn cell(self, row: u32, column: u32, height: u32, width: u32) -> Cell {
+        let alive = match self {
+            SeedPattern::Checkerboard => (row + column).is_multiple_of(2),
+            SeedPattern::Stripes => row.is_multiple_of(2),
+            SeedPattern::Cross => row == height / 2 || column == width / 2,
+            SeedPattern::BorderRing => {
+                row == 0 || column == 0 || row == height - 1 || column == width - 1
+            }
         };
+
+        Cell::from(alive)
     }
 }
 
+/// How `Universe::paste` combines the pasted cells with the ones already
+/// there.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PasteMode {
+    /// Live pasted cells are set, dead ones leave the universe untouched.
+    Or,
+    /// Every pasted cell overwrites the one under it.
+    Replace,
+    /// Live pasted cells flip the ones under them.
+    Xor,
+}
+
+impl PasteMode {
+    /// The state of a cell that was `current` after pasting `pasted` on it.
+    fn combine(self, current: bool, pasted: bool) -> Cell {
+        Cell::from(match self {
+            PasteMode::Or => current || pasted,
+            PasteMode::Replace => pasted,
+            PasteMode::Xor => current != pasted,
+        })
+    }
+}
+
+/// A summary of the population of a universe, returned by `Universe::stats`.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub live_count: u32,
+    pub total_cells: u32,
+    /// The fraction of cells that are alive, from 0.0 to 1.0.
+    pub density: f64,
+    pub generation: u64,
+}
+
+/// The values a frontend reads every frame, returned by `Universe::info` so
+/// they take a single call.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Info {
+    pub width: u32,
+    pub height: u32,
+    pub generation: u64,
+    /// The number of live cells.
+    pub population: u32,
+}
+
 #[wasm_bindgen]
 pub struct Universe {
     width: u32,
     height: u32,
-    cells: Vec<Cell>,
-    buffer_cells: Vec<Cell>,
+    cells: FixedBitSet,
+    /// The back buffer `tick` writes the next generation into before
+    /// swapping it with `cells`. Neighbors are only ever read from `cells`,
+    /// so the two must always have the same length, which every resize
+    /// keeps by replacing both together.
+    buffer_cells: FixedBitSet,
+    /// A byte per cell copy of `cells`, rebuilt on demand for `cells()`.
+    cell_bytes: RefCell<Vec<Cell>>,
+    boundaries: Boundaries,
+    neighborhood: Neighborhood,
+    rule: Rule,
+    generation: u64,
+    live_count: u32,
+    /// Snapshots of previous generations, oldest first.
+    history: VecDeque<FixedBitSet>,
+    history_capacity: usize,
+    /// Indices of the cells that changed state in the last tick.
+    changed_cells: Vec<u32>,
+    /// Neighbor counts for `tick_incremental`, built on first use.
+    neighbor_counts: Option<NeighborCounts>,
+    /// The block cache for `tick_hashed`, built on first use.
+    hashlife: Option<HashLife>,
+    /// How many generations each cell has been continuously alive, 0 for
+    /// dead and newly born cells.
+    ages: Vec<u32>,
+    /// How many states a cell can be in: alive, dead, and `states - 2`
+    /// dying states in between, as in the Generations rules. 2 is plain Life.
+    states: u8,
+    /// The state of each cell that is dying, counting up from 2, or 0 for
+    /// cells that are alive or dead.
+    dying: Vec<u8>,
+    /// The chance that a dead cell the rule brings to life is actually born,
+    /// 1 unless the simulation is made stochastic.
+    birth_probability: f64,
+    /// The random number generator behind `birth_probability`, reseeded
+    /// from the shared one by `set_birth_probability`.
+    rng: Rng,
+    /// How many cells were born or died in the last tick.
+    activity: u32,
+    /// Whether births inherit the species of their neighbors, as in the
+    /// Immigration Game.
+    immigration: bool,
+    /// The species of each live cell, 1 or 2, and 0 for dead cells. Only
+    /// kept up to date by ticks in immigration mode.
+    species: Vec<u8>,
+    /// The weight of each neighbor, in the order of `neighbor_indices`, or
+    /// `None` if every neighbor counts once.
+    weights: Option<[i32; 8]>,
+    /// How far away cells still count as neighbors, 1 for the usual
+    /// adjacent cells.
+    range: u32,
+    /// The Larger than Life rule set with `set_range_rule`, which replaces
+    /// `rule` when present.
+    range_rule: Option<RangeRule>,
+    /// The row and column of the cell registered with `watch_cell`.
+    watched_cell: Option<(u32, u32)>,
+    /// The state of the watched cell when it was registered and after every
+    /// generation since, 1 for alive.
+    cell_history: Vec<u8>,
+    /// Whether the last tick left every cell unchanged.
+    stable: bool,
+    /// The live count after each generation, oldest first, when
+    /// `track_population` is set.
+    population_history: VecDeque<u32>,
+    track_population: bool,
+    /// How many entries `population_history` keeps, or 0 for no limit.
+    population_limit: usize,
+    /// Whether inserted patterns wrap around the edges rather than being
+    /// clipped.
+    insert_wrap: bool,
+    /// Whether the universe grows whenever a live cell reaches an edge.
+    auto_expand: bool,
+    /// Whether ticks are timed with `console.time`.
+    profiling: bool,
+    /// The height, width and cells the universe was created with, for
+    /// `reset`.
+    initial: (u32, u32, FixedBitSet),
+    on_stable: Option<js_sys::Function>,
 }
 
 impl fmt::Display for Universe {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for line in self.cells.as_slice().chunks(self.width as usize) {
-            for &cell in line {
-                let symbol = if cell == Cell::Dead { '◻' } else { '◼' };
+        self.write_grid(f, '◻', '◼')
+    }
+}
+
+impl Universe {
+    /// Write the cells as a grid of `dead` and `alive` symbols, one line per
+    /// row.
+    fn write_grid<W: fmt::Write>(&self, f: &mut W, dead: char, alive: char) -> fmt::Result {
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let idx = self.get_index(row, col);
+                let symbol = if self.cells.contains(idx) {
+                    alive
+                } else {
+                    dead
+                };
                 write!(f, "{}", symbol)?;
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
 
         Ok(())
     }
-}
 
-impl Universe {
-    fn get_index(&self, row: u32, column: u32) -> usize {
-        (row * self.width + column) as usize
+    /// Build a universe at generation 0 around an initial set of cells.
+    fn from_cells(height: u32, width: u32, cells: FixedBitSet) -> Universe {
+        let buffer_cells = cells.clone();
+        let live_count = cells.count_ones() as u32;
+        let ages = vec![0; cells.len()];
+        let dying = vec![0; cells.len()];
+        let species = immigration::initial_species(&cells);
+        let initial = (height, width, cells.clone());
+
+        Universe {
+            width,
+            height,
+            cells,
+            buffer_cells,
+            cell_bytes: RefCell::new(Vec::new()),
+            boundaries: Boundaries::uniform(BoundaryMode::Toroidal),
+            neighborhood: Neighborhood::Moore,
+            rule: Rule::default(),
+            generation: 0,
+            live_count,
+            history: VecDeque::new(),
+            history_capacity: 0,
+            changed_cells: Vec::new(),
+            neighbor_counts: None,
+            hashlife: None,
+            ages,
+            states: 2,
+            dying,
+            birth_probability: 1.0,
+            rng: Rng::new(0),
+            population_history: VecDeque::new(),
+            track_population: false,
+            population_limit: 0,
+            insert_wrap: true,
+            auto_expand: false,
+            activity: 0,
+            immigration: false,
+            species,
+            weights: None,
+            range: 1,
+            range_rule: None,
+            watched_cell: None,
+            cell_history: Vec::new(),
+            stable: false,
+            profiling: false,
+            initial,
+            on_stable: None,
+        }
     }
 
-    fn live_neighbor_count(&self, row: u32, column: u32) -> u8 {
-        let mut count = 0;
+    /// Decode a universe written by `serialize`.
+    fn from_bytes(bytes: &[u8]) -> Result<Universe, JsValue> {
+        if bytes.len() < SERIALIZED_HEADER_LEN {
+            return Err(JsValue::from_str("serialized universe is truncated"));
+        }
+        if &bytes[0..4] != SERIALIZED_MAGIC {
+            return Err(JsValue::from_str("not a serialized universe"));
+        }
+        if bytes[4] != SERIALIZED_VERSION {
+            return Err(JsValue::from_str(&format!(
+                "unsupported serialized universe version {}",
+                bytes[4]
+            )));
+        }
 
-        let north = if row == 0 { self.height - 1 } else { row - 1 };
+        let width = u32::from_le_bytes(bytes[5..9].try_into().unwrap());
+        let height = u32::from_le_bytes(bytes[9..13].try_into().unwrap());
+        let generation = u64::from_le_bytes(bytes[13..21].try_into().unwrap());
+        check_size(height, width)?;
+
+        let cell_count = width as usize * height as usize;
+        let cells = FixedBitSet::from_bytes(&bytes[SERIALIZED_HEADER_LEN..], cell_count)
+            .ok_or_else(|| {
+                JsValue::from_str(&format!(
+                    "serialized universe does not hold {} cells",
+                    cell_count
+                ))
+            })?;
+
+        let mut universe = Universe::from_cells(height, width, cells);
+        universe.generation = generation;
+        Ok(universe)
+    }
 
-        let south = if row == self.height - 1 { 0 } else { row + 1 };
+    fn get_index(&self, row: u32, column: u32) -> usize {
+        cell_index(self.width, row, column)
+    }
 
-        let west = if column == 0 {
-            self.width - 1
+    /// Start a `console.time` timer called `name` if profiling is enabled.
+    fn timer(&self, name: &'static str) -> Option<Timer<'static>> {
+        if self.profiling {
+            Some(Timer::new(name))
         } else {
-            column - 1
-        };
+            None
+        }
+    }
 
-        let east = if column == self.width - 1 {
-            0
-        } else {
-            column + 1
-        };
+    /// Remember the current generation for `step_back`.
+    fn push_history(&mut self) {
+        if self.history_capacity > 0 {
+            if self.history.len() == self.history_capacity {
+                self.history.pop_front();
+            }
+            self.history.push_back(self.cells.clone());
+        }
+    }
+
+    /// The cells that may be alive in the next generation: every live cell
+    /// and its neighbors. Any other cell is dead with no live neighbors, so
+    /// it stays dead unless the rule brings such cells to life, in which
+    /// case this is `None` and every cell has to be visited.
+    fn active_cells(&self) -> Option<FixedBitSet> {
+        if self.evolve(Cell::Dead, 0) == Cell::Alive {
+            return None;
+        }
+
+        let mut active = FixedBitSet::with_capacity(self.cells.len());
+        for idx in self.cells.ones() {
+            active.set(idx, true);
+            if self.range > 1 {
+                for neighbor in self.range_neighbors(idx) {
+                    active.set(neighbor, true);
+                }
+            } else {
+                for neighbor in self.neighbor_indices(idx).iter().flatten() {
+                    active.set(*neighbor, true);
+                }
+            }
+        }
+        Some(active)
+    }
+
+    /// The state a cell with `live_neighbors` goes to under the range rule,
+    /// if one is set, or else the B/S rule, which has no section for more
+    /// than 8 neighbors.
+    fn evolve(&self, cell: Cell, live_neighbors: u8) -> Cell {
+        match &self.range_rule {
+            Some(range_rule) => range_rule.next_cell(cell, live_neighbors),
+            None => self.rule.next_cell(cell, live_neighbors.min(9)),
+        }
+    }
+
+    /// Advance the universe by a single generation.
+    ///
+    /// Only the active cells are evaluated, which makes sparse universes
+    /// much cheaper to tick. Every other cell stays dead, so the back buffer
+    /// starts out cleared.
+    fn step_once(&mut self) {
+        if self.auto_expand {
+            self.expand_to_fit();
+        }
+
+        self.push_history();
 
-        let nw = self.get_index(north, west);
-        count += self.buffer_cells[nw] as u8;
+        self.changed_cells.clear();
 
-        let n = self.get_index(north, column);
-        count += self.buffer_cells[n] as u8;
+        let candidates: Vec<usize> = match self.active_cells() {
+            Some(active) => active.ones().collect(),
+            None => (0..self.cells.len()).collect(),
+        };
 
-        let ne = self.get_index(north, east);
-        count += self.buffer_cells[ne] as u8;
+        debug_assert_eq!(self.cells.len(), self.buffer_cells.len());
+        self.buffer_cells.clear();
+
+        for idx in candidates {
+            let row = idx as u32 / self.width;
+            let col = idx as u32 % self.width;
+            let cell = Cell::from(self.cells.contains(idx));
+            let live_neighbors = self.rule_count(&self.cells, row, col);
+
+            /*
+            log!(
+            "cell[{}, {}] is initially {:?} and has {} live neighbors",
+            row,
+            col,
+            cell,
+            live_neighbors
+            );
+            */
+
+            let mut next_cell = if self.dying[idx] != 0 {
+                Cell::Dead
+            } else {
+                self.evolve(cell, live_neighbors)
+            };
+            if cell == Cell::Dead
+                && next_cell == Cell::Alive
+                && self.birth_probability < 1.0
+                && self.rng.next_f64() >= self.birth_probability
+            {
+                next_cell = Cell::Dead;
+            }
 
-        let w = self.get_index(row, west);
-        count += self.buffer_cells[w] as u8;
+            // log!("    it becomes {:?}", next_cell);
 
-        let e = self.get_index(row, east);
-        count += self.buffer_cells[e] as u8;
+            if cell != next_cell {
+                self.changed_cells.push(idx as u32);
+                match next_cell {
+                    Cell::Alive => self.live_count += 1,
+                    Cell::Dead => self.live_count -= 1,
+                }
+            }
 
-        let sw = self.get_index(south, west);
-        count += self.buffer_cells[sw] as u8;
+            self.buffer_cells.set(idx, next_cell == Cell::Alive);
+        }
 
-        let s = self.get_index(south, column);
-        count += self.buffer_cells[s] as u8;
+        if self.immigration {
+            self.update_species();
+        }
 
-        let se = self.get_index(south, east);
-        count += self.buffer_cells[se] as u8;
+        std::mem::swap(&mut self.cells, &mut self.buffer_cells);
 
-        count
+        self.finish_step();
     }
 
-    /// Get the dead and alive values of the entire universe.
-    pub fn get_cells(&self) -> &[Cell] {
-        &self.cells
+    /// Run `f` to look at future generations, then put the universe back
+    /// exactly as it was. The history, auto-expansion and stable callback
+    /// are turned off in the meantime.
+    fn look_ahead<T>(&mut self, f: impl FnOnce(&mut Universe) -> T) -> T {
+        let cells = self.cells.clone();
+        let generation = self.generation;
+        let changed_cells = self.changed_cells.clone();
+        let activity = self.activity;
+        let ages = self.ages.clone();
+        let dying = self.dying.clone();
+        let species = self.species.clone();
+        let rng = self.rng.clone();
+        let population_history = self.population_history.clone();
+        let cell_history = self.cell_history.clone();
+        let stable = self.stable;
+        let history_capacity = std::mem::replace(&mut self.history_capacity, 0);
+        let auto_expand = std::mem::replace(&mut self.auto_expand, false);
+        let on_stable = self.on_stable.take();
+
+        let result = f(self);
+
+        self.replace_cells(cells);
+        self.generation = generation;
+        self.changed_cells = changed_cells;
+        self.activity = activity;
+        self.ages = ages;
+        self.dying = dying;
+        self.species = species;
+        self.rng = rng;
+        self.population_history = population_history;
+        self.cell_history = cell_history;
+        self.stable = stable;
+        self.history_capacity = history_capacity;
+        self.auto_expand = auto_expand;
+        self.on_stable = on_stable;
+
+        result
     }
 
-    /// Set cells to be alive in a universe by passing the row and column
-    /// of each cell as an array.
-    pub fn set_cells(&mut self, cells: &[(u32, u32)]) {
-        for (row, col) in cells.iter().cloned() {
-            let idx = self.get_index(row, col);
-            self.cells[idx] = Cell::Alive;
-            self.buffer_cells[idx] = Cell::Alive;
+    /// Add the state of the watched cell to its history, if a cell is
+    /// watched and still inside the universe.
+    fn record_watched_cell(&mut self) {
+        if let Some((row, column)) = self.watched_cell {
+            if row < self.height && column < self.width {
+                let idx = self.get_index(row, column);
+                self.cell_history.push(self.cells.contains(idx) as u8);
+            }
         }
     }
-}
 
-fn generate_cells_static(i: u32) -> Cell {
-    if i % 2 == 0 || i % 7 == 0 {
-        Cell::Alive
-    } else {
-        Cell::Dead
+    /// Add the live count to the population history, if it is tracked.
+    fn record_population(&mut self) {
+        if !self.track_population {
+            return;
+        }
+
+        if self.population_limit > 0 && self.population_history.len() == self.population_limit {
+            self.population_history.pop_front();
+        }
+        self.population_history.push_back(self.live_count);
     }
-}
 
-fn generate_cells_random(_i: u32) -> Cell {
-    if js_sys::Math::random() < 0.5 {
-        Cell::Alive
-    } else {
-        Cell::Dead
+    /// The state of cell `idx` in the next generation, without the random
+    /// births of `birth_probability`.
+    fn next_cell(&self, idx: usize) -> Cell {
+        if self.dying[idx] != 0 {
+            return Cell::Dead;
+        }
+
+        let cell = Cell::from(self.cells.contains(idx));
+        let row = idx as u32 / self.width;
+        let col = idx as u32 % self.width;
+        self.evolve(cell, self.rule_count(&self.cells, row, col))
     }
-}
 
-fn generate_cells_dead(_i: u32) -> Cell {
-    Cell::Dead
-}
+    /// Whether every cell has two states, evolves deterministically by a
+    /// B/S rule and counts its adjacent neighbors once each in a universe of
+    /// fixed size, as the faster ways of advancing a generation assume.
+    /// Otherwise they fall back to `step_once`.
+    fn is_plain(&self) -> bool {
+        !self.auto_expand
+            && !self.immigration
+            && self.states == 2
+            && self.birth_probability >= 1.0
+            && self.weights.is_none()
+            && self.range == 1
+            && self.range_rule.is_none()
+    }
 
-#[wasm_bindgen]
-impl Universe {
-    pub fn new(height: u32, width: u32) -> Universe {
-        // utils::set_panic_hook();
+    /// Bookkeeping shared by every way of advancing a generation, run once
+    /// `cells` and `changed_cells` hold the new generation.
+    fn finish_step(&mut self) {
+        self.generation += 1;
 
-        if height == 0 || width == 0 {
-            panic!("Cannot create universe with 0 size");
+        // Before dying cells are added to the changed cells.
+        self.activity = self.changed_cells.len() as u32;
+        self.decay();
+
+        // Dead cells that didn't just die already have an age of 0, so only
+        // live and changed cells need to be visited.
+        for idx in self.cells.ones() {
+            self.ages[idx] += 1;
+        }
+        for &idx in &self.changed_cells {
+            self.ages[idx as usize] = 0;
         }
 
-        let cells: Vec<Cell> = (0..width * height).map(generate_cells_static).collect();
-        let buffer_cells = cells.clone();
+        self.record_population();
+        self.record_watched_cell();
 
-        Universe {
-            width,
-            height,
-            cells,
-            buffer_cells,
+        let was_stable = self.stable;
+        self.stable = self.changed_cells.is_empty();
+        if self.stable && !was_stable {
+            if let Some(on_stable) = &self.on_stable {
+                // An exception in the callback shouldn't stop the simulation.
+                let _ = on_stable.call0(&JsValue::NULL);
+            }
         }
     }
 
-    pub fn new_random(height: u32, width: u32) -> Universe {
-        utils::set_panic_hook();
-
-        if height == 0 || width == 0 {
-            panic!("Cannot create universe with 0 size");
+    /// Set a single cell in both buffers, keeping the live count in sync.
+    fn set_cell_state(&mut self, idx: usize, state: Cell) {
+        let alive = state == Cell::Alive;
+        self.dying[idx] = 0;
+        if self.cells.contains(idx) != alive {
+            self.ages[idx] = 0;
+            self.species[idx] = alive as u8 * immigration::FIRST_SPECIES;
+            if alive {
+                self.live_count += 1;
+            } else {
+                self.live_count -= 1;
+            }
         }
 
-        let cells: Vec<Cell> = (0..width * height).map(generate_cells_random).collect();
-        let buffer_cells = cells.clone();
+        self.cells.set(idx, alive);
+        self.buffer_cells.set(idx, alive);
+    }
 
-        Universe {
-            width,
-            height,
-            cells,
-            buffer_cells,
-        }
+    /// Replace every cell at once, keeping both buffers and the live count in
+    /// sync.
+    fn replace_cells(&mut self, cells: FixedBitSet) {
+        self.live_count = cells.count_ones() as u32;
+        self.ages = vec![0; cells.len()];
+        self.dying = vec![0; cells.len()];
+        self.species = immigration::initial_species(&cells);
+        self.buffer_cells = cells.clone();
+        self.cells = cells;
     }
 
-    pub fn new_dead(height: u32, width: u32) -> Universe {
-        utils::set_panic_hook();
+    /// Rebuild the universe at the given size, taking each new cell from the
+    /// old cell at the position `source` maps it to.
+    fn remap(&mut self, new_height: u32, new_width: u32, source: impl Fn(u32, u32) -> (u32, u32)) {
+        let cells: FixedBitSet = (0..new_height)
+            .flat_map(|row| (0..new_width).map(move |col| (row, col)))
+            .map(|(row, col)| {
+                let (row, col) = source(row, col);
+                Cell::from(self.cells.contains(self.get_index(row, col)))
+            })
+            .collect();
 
-        if height == 0 || width == 0 {
-            panic!("Cannot create universe with 0 size");
+        self.width = new_width;
+        self.height = new_height;
+        self.replace_cells(cells);
+        self.history.clear();
+        self.changed_cells.clear();
+    }
+
+    /// The index of a cell, or an error if it lies outside the universe.
+    fn checked_index(&self, row: u32, column: u32) -> Result<usize, JsValue> {
+        if row >= self.height || column >= self.width {
+            return Err(JsValue::from_str(&format!(
+                "cell ({}, {}) is outside the {}x{} universe",
+                row, column, self.height, self.width
+            )));
         }
 
-        let cells: Vec<Cell> = (0..width * height).map(generate_cells_dead).collect();
-        let buffer_cells = cells.clone();
+        Ok(self.get_index(row, column))
+    }
 
-        Universe {
-            width,
-            height,
-            cells,
-            buffer_cells,
+    /// `idx` itself, or an error if no cell has that index.
+    fn checked_flat_index(&self, idx: usize) -> Result<usize, JsValue> {
+        if idx >= self.cells.len() {
+            return Err(JsValue::from_str(&format!(
+                "cell index {} is outside the {}x{} universe",
+                idx, self.height, self.width
+            )));
+        }
+
+        Ok(idx)
+    }
+
+    /// The number of live neighbors of a cell in `cells` as the rule sees
+    /// it: a plain count, or with `set_weights` the sum of the weights of
+    /// the live neighbors. Weights only apply to a range of 1.
+    fn rule_count(&self, cells: &FixedBitSet, row: u32, column: u32) -> u8 {
+        if self.range > 1 {
+            return self.range_count(cells, row, column);
         }
+
+        let weights = match &self.weights {
+            Some(weights) => weights,
+            None => {
+                return count_live_neighbors(
+                    cells,
+                    self.width,
+                    self.height,
+                    self.boundaries,
+                    self.neighborhood,
+                    row,
+                    column,
+                )
+            }
+        };
+
+        let sum: i32 = self
+            .neighbor_indices(self.get_index(row, column))
+            .iter()
+            .zip(weights)
+            .filter(|(neighbor, _)| neighbor.is_some_and(|neighbor| cells.contains(neighbor)))
+            .map(|(_, &weight)| weight)
+            .sum();
+
+        // `evolve` caps the sum for B/S rules, so only range rules see sums
+        // above 8.
+        sum.clamp(0, u8::MAX as i32) as u8
+    }
+
+    /// The indices of the cells touching cell `idx` orthogonally or
+    /// diagonally, whatever the neighborhood, across edges that wrap around
+    /// or mirror. The cell itself is included.
+    fn touching_cells(&self, idx: usize) -> Vec<usize> {
+        let row = idx as u32 / self.width;
+        let column = idx as u32 % self.width;
+        let rows = [
+            self.boundaries.y.previous(row, self.height),
+            Some(row),
+            self.boundaries.y.next(row, self.height),
+        ];
+        let columns = [
+            self.boundaries.x.previous(column, self.width),
+            Some(column),
+            self.boundaries.x.next(column, self.width),
+        ];
+
+        rows.iter()
+            .flatten()
+            .flat_map(|&row| {
+                columns
+                    .iter()
+                    .flatten()
+                    .map(move |&column| self.get_index(row, column))
+            })
+            .collect()
+    }
+
+    /// Get the dead and alive values of the entire universe.
+    pub fn get_cells(&self) -> Vec<Cell> {
+        (0..self.cells.len())
+            .map(|idx| Cell::from(self.cells.contains(idx)))
+            .collect()
     }
 
-    pub fn toggle_cell(&mut self, row: u32, column: u32) {
-        let idx = self.get_index(row, column);
-        self.cells[idx].toggle();
+    /// The smallest rectangle containing every live cell, as
+    /// `(min_row, min_col, max_row, max_col)`, or `None` if nothing is alive.
+    ///
+    /// The edges of a toroidal universe are ignored, so a pattern wrapping
+    /// around an edge reports bounds spanning the whole universe.
+    pub fn live_bounds(&self) -> Option<(u32, u32, u32, u32)> {
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if !self.cells.contains(self.get_index(row, col)) {
+                    continue;
+                }
+
+                bounds = Some(match bounds {
+                    Some((min_row, min_col, max_row, max_col)) => (
+                        min_row.min(row),
+                        min_col.min(col),
+                        max_row.max(row),
+                        max_col.max(col),
+                    ),
+                    None => (row, col, row, col),
+                });
+            }
+        }
+
+        bounds
     }
 
-    pub fn insert_glider(&mut self, row: u32, column: u32) {
-        let mut coords: Vec<(u32, u32)> = Vec::new();
+    /// The universe as RGBA pixels, one per cell in row-major order, ready
+    /// to wrap in an `ImageData` for `putImageData`.
+    pub fn to_rgba(&self, alive: [u8; 4], dead: [u8; 4]) -> Vec<u8> {
+        (0..self.cells.len())
+            .flat_map(|idx| {
+                if self.cells.contains(idx) {
+                    alive
+                } else {
+                    dead
+                }
+            })
+            .collect()
+    }
 
-        coords.push((row - 1, column - 1));
-        coords.push((row, column));
-        coords.push((row, column + 1));
-        coords.push((row + 1, column - 1));
-        coords.push((row + 1, column));
+    /// Set cells alive at `(row, column)` offsets from an anchor cell,
+    /// wrapping around the edges of the universe, or dropping the ones that
+    /// fall outside it if `insert_wrap` is off.
+    fn insert_offsets(&mut self, row: u32, column: u32, offsets: &[(i32, i32)]) {
+        if !self.insert_wrap {
+            self.insert_offsets_clipped(row, column, offsets);
+            return;
+        }
 
-        coords = coords
+        let coords: Vec<(u32, u32)> = offsets
             .iter()
-            .map(|coord| (coord.0 % self.height, coord.1 % self.width))
+            .map(|&(d_row, d_col)| {
+                (
+                    wrap_coord(row, d_row, self.height),
+                    wrap_coord(column, d_col, self.width),
+                )
+            })
             .collect();
         self.set_cells(&coords);
     }
 
-    pub fn insert_pulsar(&mut self, row: u32, column: u32) {
-        let mut coords: Vec<(u32, u32)> = Vec::new();
-
-        coords.push((row - 6, column - 4));
-        coords.push((row - 6, column - 3));
-        coords.push((row - 6, column - 2));
-        coords.push((row - 6, column + 4));
-        coords.push((row - 6, column + 3));
-        coords.push((row - 6, column + 2));
-
-        coords.push((row + 6, column - 4));
-        coords.push((row + 6, column - 3));
-        coords.push((row + 6, column - 2));
-        coords.push((row + 6, column + 4));
-        coords.push((row + 6, column + 3));
-        coords.push((row + 6, column + 2));
-
-        coords.push((row - 1, column - 4));
-        coords.push((row - 1, column - 3));
-        coords.push((row - 1, column - 2));
-        coords.push((row - 1, column + 4));
-        coords.push((row - 1, column + 3));
-        coords.push((row - 1, column + 2));
-
-        coords.push((row + 1, column - 4));
-        coords.push((row + 1, column - 3));
-        coords.push((row + 1, column - 2));
-        coords.push((row + 1, column + 4));
-        coords.push((row + 1, column + 3));
-        coords.push((row + 1, column + 2));
-
-        coords.push((row - 4, column - 6));
-        coords.push((row - 3, column - 6));
-        coords.push((row - 2, column - 6));
-        coords.push((row + 4, column - 6));
-        coords.push((row + 3, column - 6));
-        coords.push((row + 2, column - 6));
-
-        coords.push((row - 4, column + 6));
-        coords.push((row - 3, column + 6));
-        coords.push((row - 2, column + 6));
-        coords.push((row + 4, column + 6));
-        coords.push((row + 3, column + 6));
-        coords.push((row + 2, column + 6));
-
-        coords.push((row - 4, column - 1));
-        coords.push((row - 3, column - 1));
-        coords.push((row - 2, column - 1));
-        coords.push((row + 4, column - 1));
-        coords.push((row + 3, column - 1));
-        coords.push((row + 2, column - 1));
-
-        coords.push((row - 4, column + 1));
-        coords.push((row - 3, column + 1));
-        coords.push((row - 2, column + 1));
-        coords.push((row + 4, column + 1));
-        coords.push((row + 3, column + 1));
-        coords.push((row + 2, column + 1));
-
-        coords = coords
+    /// Set cells alive at `(row, column)` offsets from an anchor cell,
+    /// dropping the ones that fall outside the universe.
+    fn insert_offsets_clipped(&mut self, row: u32, column: u32, offsets: &[(i32, i32)]) {
+        let coords: Vec<(u32, u32)> = offsets
             .iter()
-            .map(|coord| (coord.0 % self.height, coord.1 % self.width))
+            .filter_map(|&(d_row, d_col)| {
+                Some((
+                    clip_coord(row, d_row, self.height)?,
+                    clip_coord(column, d_col, self.width)?,
+                ))
+            })
             .collect();
         self.set_cells(&coords);
     }
 
-    pub fn width(&self) -> u32 {
-        self.width
+    /// Where a cell inserted at `row` and `column` ends up: wrapped around
+    /// the edges of the universe, or `None` if it is outside and
+    /// `insert_wrap` is off.
+    fn insert_position(&self, row: u64, column: u64) -> Option<(u32, u32)> {
+        if self.insert_wrap {
+            Some((
+                (row % self.height as u64) as u32,
+                (column % self.width as u64) as u32,
+            ))
+        } else if row < self.height as u64 && column < self.width as u64 {
+            Some((row as u32, column as u32))
+        } else {
+            None
+        }
     }
 
-    /// Set the width of the universe.
-    ///
-    /// Resets all cells to the dead cell state.
-    pub fn set_width(&mut self, width: u32) {
-        self.width = width;
-        self.cells = (0..self.width * self.height).map(|_i| Cell::Dead).collect();
+    /// Set cells to be alive in a universe by passing the row and column
+    /// of each cell as an array. Cells outside the universe wrap around, or
+    /// are dropped if `insert_wrap` is off.
+    pub fn set_cells(&mut self, cells: &[(u32, u32)]) {
+        self.apply_cells(cells, Cell::Alive);
     }
 
-    pub fn height(&self) -> u32 {
-        self.height
+    /// Set the cells at each `(row, column)` to `state`, like `set_cells`
+    /// but also able to clear them, for example to erase a pattern stamped
+    /// earlier.
+    pub fn apply_cells(&mut self, cells: &[(u32, u32)], state: Cell) {
+        for &(row, col) in cells {
+            if let Some((row, col)) = self.insert_position(row as u64, col as u64) {
+                let idx = self.get_index(row, col);
+                self.set_cell_state(idx, state);
+            }
+        }
     }
 
-    /// Set the height of the universe.
-    ///
-    /// Resets all cells to the dead cell state.
-    pub fn set_height(&mut self, height: u32) {
-        self.height = height;
-        self.cells = (0..self.width * self.height).map(|_i| Cell::Dead).collect();
+    /// `tick_within` with `now` as the clock, in milliseconds. Only public
+    /// so the tests can drive the clock.
+    #[doc(hidden)]
+    pub fn tick_within_clock(&mut self, budget_ms: f64, mut now: impl FnMut() -> f64) -> u32 {
+        let start = now();
+        let mut ticks = 0;
+        while now() - start < budget_ms {
+            self.step_once();
+            ticks += 1;
+        }
+        ticks
     }
+}
 
-    pub fn cells(&self) -> *const Cell {
-        self.cells.as_ptr()
+/// The flat index of the cell at `row` and `column` in a universe `width`
+/// cells wide. It is worked out in `usize` rather than `u32`, which would
+/// overflow once a universe has more than `u32::MAX` cells.
+fn cell_index(width: u32, row: u32, column: u32) -> usize {
+    row as usize * width as usize + column as usize
+}
+
+/// Move `coord` by `offset` on an axis of length `size`, wrapping around at
+/// both ends.
+fn wrap_coord(coord: u32, offset: i32, size: u32) -> u32 {
+    (coord as i64 + offset as i64).rem_euclid(size as i64) as u32
+}
+
+/// Move `coord` by `offset` on an axis of length `size`, or `None` if that
+/// falls off either end.
+fn clip_coord(coord: u32, offset: i32, size: u32) -> Option<u32> {
+    let coord = coord as i64 + offset as i64;
+    if (0..size as i64).contains(&coord) {
+        Some(coord as u32)
+    } else {
+        None
     }
+}
 
-    pub fn render(&self) -> String {
-        self.to_string()
+/// The number of live neighbors of a cell in `cells`, a `height` by `width`
+/// grid. Kept apart from `Universe` so it can be shared across threads.
+fn count_live_neighbors(
+    cells: &FixedBitSet,
+    width: u32,
+    height: u32,
+    boundaries: Boundaries,
+    neighborhood: Neighborhood,
+    row: u32,
+    column: u32,
+) -> u8 {
+    // The value of a neighbor, where `None` means the neighbor is outside
+    // the universe and counts as dead.
+    let neighbor_cell = |row: Option<u32>, column: Option<u32>| match (row, column) {
+        (Some(row), Some(column)) => cells.contains(cell_index(width, row, column)) as u8,
+        _ => 0,
+    };
+
+    let mut count = 0;
+
+    let north = boundaries.y.previous(row, height);
+
+    let south = boundaries.y.next(row, height);
+
+    let west = boundaries.x.previous(column, width);
+
+    let east = boundaries.x.next(column, width);
+
+    let (row, column) = (Some(row), Some(column));
+
+    count += neighbor_cell(north, column);
+    count += neighbor_cell(row, west);
+    count += neighbor_cell(row, east);
+    count += neighbor_cell(south, column);
+
+    if neighborhood == Neighborhood::Moore {
+        count += neighbor_cell(north, west);
+        count += neighbor_cell(north, east);
+        count += neighbor_cell(south, west);
+        count += neighbor_cell(south, east);
     }
 
-    pub fn tick(&mut self) {
-        let _timer = Timer::new("Universe::tick");
+    count
+}
 
+/// An error if a universe can't be created with the given size.
+fn check_size(height: u32, width: u32) -> Result<(), JsValue> {
+    if height == 0 || width == 0 {
+        return Err(JsValue::from_str(&format!(
+            "cannot create a {}x{} universe, both dimensions must be at least 1",
+            height, width
+        )));
+    }
 
-        {
-            let _timer = Timer::new("new generation");
+    let max_cells = MAX_CELLS.load(Ordering::Relaxed);
+    match width.checked_mul(height) {
+        Some(cells) if cells <= max_cells => Ok(()),
+        _ => Err(JsValue::from_str(&format!(
+            "cannot create a {}x{} universe, it would have more than {} cells",
+            height, width, max_cells
+        ))),
+    }
+}
 
-            for row in 0..self.height {
-                for col in 0..self.width {
-                    let idx = self.get_index(row, col);
-                    let cell = self.buffer_cells[idx];
-                    let live_neighbors = self.live_neighbor_count(row, col);
+/// Set the largest number of cells a universe can be created or resized
+/// with, to catch sizes that would run out of memory. Defaults to 67,108,864,
+/// an 8192x8192 universe.
+#[wasm_bindgen]
+pub fn set_max_cells(limit: u32) {
+    MAX_CELLS.store(limit, Ordering::Relaxed);
+}
 
-                    /*
-                    log!(
-                    "cell[{}, {}] is initially {:?} and has {} live neighbors",
-                    row,
-                    col,
-                    cell,
-                    live_neighbors
-                    );
-                    */
-
-                    let next_cell = match (cell, live_neighbors) {
-                        // Rule 1: Any live cell with fewer than two live neighbours
-                        // dies, as if caused by underpopulation.
-                        (Cell::Alive, x) if x < 2 => Cell::Dead,
-                        // Rule 2: Any live cell with two or three live neighbours
-                        // lives on to the next generation.
-                        (Cell::Alive, 2) | (Cell::Alive, 3) => Cell::Alive,
-                        // Rule 3: Any live cell with more than three live
-                        // neighbours dies, as if by overpopulation.
-                        (Cell::Alive, x) if x > 3 => Cell::Dead,
-                        // Rule 4: Any dead cell with exactly three live neighbours
-                        // becomes a live cell, as if by reproduction.
-                        (Cell::Dead, 3) => Cell::Alive,
-                        // All other cells remain in the same state.
-                        (otherwise, _) => otherwise,
-                    };
-
-                    // log!("    it becomes {:?}", next_cell);
-
-                    self.cells[idx] = next_cell;
-                }
-            }
+fn generate_cells_static(i: u32) -> Cell {
+    if i.is_multiple_of(2) || i.is_multiple_of(7) {
+        Cell::Alive
+    } else {
+        Cell::Dead
+    }
+}
 
-            for row in 0..self.height {
-                for col in 0..self.width {
-                    let idx = self.get_index(row, col);
-                    let cell = self.cells[idx];
-                    let next_cell = match cell {
-                        Cell::Alive => Cell::Alive,
-                        Cell::Dead => Cell::Dead,
-                    };
-                    self.buffer_cells[idx] = next_cell;
-                }
-            }
-        }
+fn generate_cells_random(rng: &mut Rng, density: f64) -> Cell {
+    Cell::from(rng.next_f64() < density)
+}
+
+fn generate_cells_dead(_i: u32) -> Cell {
+    Cell::Dead
+}
+
+/// Restart the random number generator behind `Universe::new_random`,
+/// `Universe::new_random_with_density` and the births of
+/// `Universe::set_birth_probability` from `seed`, so the universes created
+/// after it are the same from run to run. Without a call the generator is
+/// seeded from `Math.random`, so every page load gets different boards.
+#[wasm_bindgen]
+pub fn seed_rng(seed: u64) {
+    rng::seed_default(seed);
+}
+
+#[wasm_bindgen]
+impl Universe {
+    pub fn new(height: u32, width: u32) -> Result<Universe, JsValue> {
+        // utils::set_panic_hook();
+
+        check_size(height, width)?;
+
+        let cells = (0..width * height).map(generate_cells_static).collect();
+
+        Ok(Universe::from_cells(height, width, cells))
+    }
+
+    /// Create a universe with each cell alive with probability 0.5, drawn
+    /// from the generator seeded with `seed_rng`.
+    pub fn new_random(height: u32, width: u32) -> Result<Universe, JsValue> {
+        Universe::new_random_with_density(height, width, 0.5)
+    }
+
+    /// Create a universe with each cell alive with probability `density`,
+    /// clamped to between 0 and 1.
+    pub fn new_random_with_density(
+        height: u32,
+        width: u32,
+        density: f64,
+    ) -> Result<Universe, JsValue> {
+        utils::set_panic_hook();
+
+        check_size(height, width)?;
+
+        let density = density.clamp(0.0, 1.0);
+        let cells = rng::with_default(|rng| {
+            (0..width * height)
+                .map(|_i| generate_cells_random(rng, density))
+                .collect()
+        });
+
+        Ok(Universe::from_cells(height, width, cells))
+    }
+
+    pub fn new_dead(height: u32, width: u32) -> Result<Universe, JsValue> {
+        utils::set_panic_hook();
+
+        check_size(height, width)?;
+
+        let cells = (0..width * height).map(generate_cells_dead).collect();
+
+        Ok(Universe::from_cells(height, width, cells))
+    }
+
+    /// Create a universe seeded with one of the built-in `SeedPattern`s.
+    pub fn new_pattern(height: u32, width: u32, kind: SeedPattern) -> Result<Universe, JsValue> {
+        utils::set_panic_hook();
+
+        check_size(height, width)?;
+
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |col| kind.cell(row, col, height, width)))
+            .collect();
+
+        Ok(Universe::from_cells(height, width, cells))
+    }
+
+    /// Create a universe where the cell at each flat index `idx` is alive
+    /// when `cb(idx)` returns a truthy value. If `cb` throws, the exception
+    /// is returned as the error.
+    pub fn new_from_fn(height: u32, width: u32, cb: js_sys::Function) -> Result<Universe, JsValue> {
+        utils::set_panic_hook();
+
+        check_size(height, width)?;
+
+        let cells = (0..width * height)
+            .map(|idx| {
+                let alive = cb.call1(&JsValue::NULL, &JsValue::from(idx))?;
+                Ok(Cell::from(alive.is_truthy()))
+            })
+            .collect::<Result<FixedBitSet, JsValue>>()?;
+
+        Ok(Universe::from_cells(height, width, cells))
+    }
+
+    /// The state of the cell at `row` and `column`.
+    pub fn get_cell(&self, row: u32, column: u32) -> Result<Cell, JsValue> {
+        let idx = self.checked_index(row, column)?;
+        Ok(Cell::from(self.cells.contains(idx)))
+    }
+
+    /// Whether the cell at `row` and `column` is alive.
+    pub fn is_alive(&self, row: u32, column: u32) -> Result<bool, JsValue> {
+        Ok(self.get_cell(row, column)? == Cell::Alive)
+    }
+
+    /// The flat index of the cell at `row` and `column`, as used by
+    /// `cells()` and the `*_at_index` methods.
+    #[wasm_bindgen(js_name = get_index)]
+    pub fn get_index_pub(&self, row: u32, column: u32) -> Result<usize, JsValue> {
+        self.checked_index(row, column)
+    }
+
+    /// The state of the cell at flat index `idx`.
+    pub fn cell_at_index(&self, idx: usize) -> Result<Cell, JsValue> {
+        let idx = self.checked_flat_index(idx)?;
+        Ok(Cell::from(self.cells.contains(idx)))
+    }
+
+    /// Set the state of the cell at flat index `idx`.
+    pub fn set_cell_at_index(&mut self, idx: usize, state: Cell) -> Result<(), JsValue> {
+        let idx = self.checked_flat_index(idx)?;
+        self.set_cell_state(idx, state);
+        Ok(())
+    }
+
+    /// The number of live neighbors of the cell at `row` and `column`, as
+    /// used by `tick` under the current boundary mode. With `set_weights`
+    /// this is the sum of the weights of the live neighbors.
+    pub fn neighbor_count(&self, row: u32, column: u32) -> Result<u8, JsValue> {
+        self.checked_index(row, column)?;
+        Ok(self.rule_count(&self.cells, row, column))
+    }
+
+    /// The number of live neighbors of every cell, in row-major order,
+    /// weighted like `neighbor_count`.
+    pub fn neighbor_counts(&self) -> Vec<u8> {
+        (0..self.height)
+            .flat_map(|row| (0..self.width).map(move |col| (row, col)))
+            .map(|(row, col)| self.rule_count(&self.cells, row, col))
+            .collect()
+    }
+
+    /// Kill every cell in place, keeping the size and generation.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.buffer_cells.clear();
+        self.live_count = 0;
+        for age in self.ages.iter_mut() {
+            *age = 0;
+        }
+        for state in self.dying.iter_mut() {
+            *state = 0;
+        }
+        for species in self.species.iter_mut() {
+            *species = 0;
+        }
+    }
+
+    /// Bring every dead cell to life and kill every live one.
+    pub fn invert(&mut self) {
+        let mut cells = self.cells.clone();
+        cells.invert();
+        self.replace_cells(cells);
+    }
+
+    /// Restore the size and cells the universe was created with, and start
+    /// counting generations from 0 again.
+    pub fn reset(&mut self) {
+        let (height, width, cells) = self.initial.clone();
+        self.height = height;
+        self.width = width;
+        self.replace_cells(cells);
+        self.generation = 0;
+        self.history.clear();
+        self.changed_cells.clear();
+        self.stable = false;
+    }
+
+    /// Fill the universe in place with random cells, each alive with
+    /// probability `density`. The same `seed` always gives the same board.
+    pub fn randomize(&mut self, density: f64, seed: u64) {
+        let mut rng = Rng::new(seed);
+        let cells = (0..self.cells.len())
+            .map(|_i| Cell::from(rng.next_f64() < density))
+            .collect();
+        self.replace_cells(cells);
+    }
+
+    /// Fill the universe in place with exactly `n` live cells at random
+    /// positions, or every cell if `n` is larger than the universe. The same
+    /// `seed` always gives the same board.
+    pub fn randomize_count(&mut self, n: u32, seed: u64) {
+        let mut rng = Rng::new(seed);
+        let mut indices: Vec<usize> = (0..self.cells.len()).collect();
+        let n = (n as usize).min(indices.len());
+
+        // A partial Fisher-Yates shuffle, picking the first `n` indices.
+        let mut cells = FixedBitSet::with_capacity(indices.len());
+        for i in 0..n {
+            let j = i + rng.below((indices.len() - i) as u64) as usize;
+            indices.swap(i, j);
+            cells.set(indices[i], true);
+        }
+        self.replace_cells(cells);
+    }
+
+    /// Set every cell in the block of `height` rows and `width` columns
+    /// whose top-left cell is at `top` and `left`, so rows `top` through
+    /// `top + height - 1` and columns `left` through `left + width - 1` are
+    /// included. The block is clipped to the edges of the universe.
+    pub fn set_region(&mut self, top: u32, left: u32, height: u32, width: u32, state: Cell) {
+        let bottom = top.saturating_add(height).min(self.height);
+        let right = left.saturating_add(width).min(self.width);
+
+        for row in top..bottom {
+            for col in left..right {
+                let idx = self.get_index(row, col);
+                self.set_cell_state(idx, state);
+            }
+        }
+    }
+
+    /// Flip the cell at `row` and `column` between dead and alive, or
+    /// return an error if it lies outside the universe.
+    pub fn toggle_cell(&mut self, row: u32, column: u32) -> Result<(), JsValue> {
+        let idx = self.checked_index(row, column)?;
+        let state = Cell::from(!self.cells.contains(idx));
+        self.set_cell_state(idx, state);
+        Ok(())
+    }
+
+    /// Insert a glider centered on `row` and `column`, travelling in
+    /// `direction`.
+    pub fn insert_glider(&mut self, row: u32, column: u32, direction: GliderDirection) {
+        self.insert_offsets(row, column, &direction.glider_offsets());
+    }
+
+    /// Insert `count` south-east bound gliders in a diagonal line, the first
+    /// centered on `row` and `column` and each next one `spacing` rows and
+    /// columns further, wrapping around the edges. With a spacing below 3
+    /// the gliders overlap.
+    pub fn insert_glider_stream(&mut self, row: u32, column: u32, count: u32, spacing: u32) {
+        for i in 0..count as u64 {
+            let offset = i * spacing as u64;
+            if let Some((row, column)) =
+                self.insert_position(row as u64 + offset, column as u64 + offset)
+            {
+                self.insert_offsets(row, column, Pattern::Glider.offsets());
+            }
+        }
+    }
+
+    pub fn insert_pulsar(&mut self, row: u32, column: u32) {
+        self.insert_offsets(row, column, Pattern::Pulsar.offsets());
+    }
+
+    /// Insert a horizontal blinker centered on `row` and `column`.
+    pub fn insert_blinker(&mut self, row: u32, column: u32) {
+        self.insert_offsets(row, column, Pattern::Blinker.offsets());
+    }
+
+    /// Insert a beacon whose upper block has its bottom-right cell at `row`
+    /// and `column`.
+    pub fn insert_beacon(&mut self, row: u32, column: u32) {
+        self.insert_offsets(row, column, Pattern::Beacon.offsets());
+    }
+
+    /// Insert an east-bound lightweight spaceship (LWSS) centered on `row`
+    /// and `column`.
+    pub fn insert_lwss(&mut self, row: u32, column: u32) {
+        self.insert_offsets(row, column, Pattern::Lwss.offsets());
+    }
+
+    /// Insert a Gosper glider gun with its top-left corner at `row` and
+    /// `column`. It fires a south-east bound glider every 30 generations.
+    pub fn insert_gosper_glider_gun(&mut self, row: u32, column: u32) {
+        self.insert_offsets(row, column, Pattern::GosperGliderGun.offsets());
+    }
+
+    /// Insert a built-in pattern by name, anchored at `row` and `column` like
+    /// the matching `insert_*` method. The names are `glider`, `pulsar`,
+    /// `blinker`, `beacon`, `lwss`, `gun` (a Gosper glider gun) and `block`.
+    pub fn insert_named(&mut self, name: &str, row: u32, column: u32) -> Result<(), JsValue> {
+        let pattern: Pattern = name
+            .parse()
+            .map_err(|err: String| JsValue::from_str(&err))?;
+        self.insert_offsets(row, column, pattern.offsets());
+        Ok(())
+    }
+
+    /// Stamp a `pat_height` by `pat_width` pattern with its top-left corner
+    /// at `top` and `left`, wrapping around the edges of the universe.
+    ///
+    /// `data` holds one byte per cell in row-major order, non-zero for
+    /// alive. Dead cells in the pattern leave the universe untouched unless
+    /// `overwrite` is set, in which case they are cleared.
+    pub fn stamp(
+        &mut self,
+        top: u32,
+        left: u32,
+        pat_height: u32,
+        pat_width: u32,
+        data: &[u8],
+        overwrite: bool,
+    ) -> Result<(), JsValue> {
+        if data.len() as u64 != pat_height as u64 * pat_width as u64 {
+            return Err(JsValue::from_str(&format!(
+                "a {}x{} pattern needs {} cells, got {}",
+                pat_height,
+                pat_width,
+                pat_height as u64 * pat_width as u64,
+                data.len()
+            )));
+        }
+
+        for (i, &value) in data.iter().enumerate() {
+            if value == 0 && !overwrite {
+                continue;
+            }
+
+            let row = top as u64 + i as u64 / pat_width as u64;
+            let col = left as u64 + i as u64 % pat_width as u64;
+            if let Some((row, col)) = self.insert_position(row, col) {
+                let idx = self.get_index(row, col);
+                self.set_cell_state(idx, Cell::from(value != 0));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Copy the cells of `other` onto this universe with its top-left cell
+    /// at `top` and `left`, combining them with the cells already there as
+    /// `mode` says. Cells past the edges wrap around, or are dropped if
+    /// `insert_wrap` is off. Together with `subgrid` this makes a clipboard.
+    pub fn paste(&mut self, top: u32, left: u32, other: &Universe, mode: PasteMode) {
+        for row in 0..other.height {
+            for col in 0..other.width {
+                let pasted = other.cells.contains(other.get_index(row, col));
+                if let Some((row, col)) =
+                    self.insert_position(top as u64 + row as u64, left as u64 + col as u64)
+                {
+                    let idx = self.get_index(row, col);
+                    let current = self.cells.contains(idx);
+                    let state = mode.combine(current, pasted);
+                    if state != Cell::from(current) {
+                        self.set_cell_state(idx, state);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Insert `pattern` anchored at `row` and `column` like the `insert_*`
+    /// methods, but drop any cells that fall outside the universe instead
+    /// of wrapping them around to the opposite edge.
+    pub fn insert_clipped(&mut self, pattern: Pattern, row: u32, column: u32) {
+        self.insert_offsets_clipped(row, column, pattern.offsets());
+    }
+
+    /// The boundary mode set with `set_boundary_mode`. Once the axes were
+    /// set apart with `set_boundary_x` or `set_boundary_y` there is no
+    /// single mode, and this only reports the left and right edges, the same
+    /// as `boundary_x`. Use `boundary_x` and `boundary_y` to read both.
+    pub fn boundary_mode(&self) -> BoundaryMode {
+        self.boundaries.x
+    }
+
+    /// Set how cells on every edge of the universe find their neighbors.
+    pub fn set_boundary_mode(&mut self, boundary_mode: BoundaryMode) {
+        self.boundaries = Boundaries::uniform(boundary_mode);
+    }
+
+    pub fn boundary_x(&self) -> BoundaryMode {
+        self.boundaries.x
+    }
+
+    /// Set how cells on the left and right edges find their neighbors,
+    /// leaving the top and bottom edges alone.
+    pub fn set_boundary_x(&mut self, boundary_mode: BoundaryMode) {
+        self.boundaries.x = boundary_mode;
+    }
+
+    pub fn boundary_y(&self) -> BoundaryMode {
+        self.boundaries.y
+    }
+
+    /// Set how cells on the top and bottom edges find their neighbors,
+    /// leaving the left and right edges alone. With a toroidal x and a dead
+    /// y, the universe is a cylinder.
+    pub fn set_boundary_y(&mut self, boundary_mode: BoundaryMode) {
+        self.boundaries.y = boundary_mode;
+    }
+
+    pub fn neighborhood(&self) -> Neighborhood {
+        self.neighborhood
+    }
+
+    /// Set which cells count as neighbors. Defaults to the Moore
+    /// neighborhood that Conway's Game of Life uses.
+    pub fn set_neighborhood(&mut self, kind: Neighborhood) {
+        self.neighborhood = kind;
+    }
+
+    /// The rule the universe evolves by: the one set with `set_range_rule`
+    /// if there is one, otherwise the B/S rule.
+    pub fn rule(&self) -> String {
+        match &self.range_rule {
+            Some(range_rule) => range_rule.to_string(),
+            None => self.rule.to_string(),
+        }
+    }
+
+    /// Set the rule the universe evolves by in B/S notation, such as
+    /// `B3/S23` for Conway's Game of Life or `B36/S23` for HighLife.
+    /// This replaces any rule set with `set_range_rule`.
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), JsValue> {
+        self.rule = rule
+            .parse()
+            .map_err(|err: String| JsValue::from_str(&err))?;
+        self.range_rule = None;
+        Ok(())
+    }
+
+    pub fn range(&self) -> u32 {
+        self.range
+    }
+
+    /// Count every cell within `r` rows and columns as a neighbor, rather
+    /// than only the adjacent ones, as in the Larger than Life rules. With
+    /// the von Neumann neighborhood the cells within `r` steps orthogonally
+    /// are counted instead. `r` is 1 by default and can be at most 7.
+    ///
+    /// B/S rules have no section for more than 8 neighbors, so larger
+    /// ranges are usually paired with `set_range_rule`. Neighbor weights
+    /// only apply to a range of 1.
+    pub fn set_range(&mut self, r: u32) -> Result<(), JsValue> {
+        if r == 0 || r > ltl::MAX_RANGE {
+            return Err(JsValue::from_str(&format!(
+                "the neighborhood range must be between 1 and {}, not {}",
+                ltl::MAX_RANGE,
+                r
+            )));
+        }
+
+        self.range = r;
+        Ok(())
+    }
+
+    /// Set a Larger than Life rule, where cells are born and survive when
+    /// their live neighbor count falls in a range, such as
+    /// `B34..45/S33..57` for Bosco's Rule at a range of 5. A single count
+    /// like `B3` is a range of one. `set_rule` goes back to a B/S rule.
+    pub fn set_range_rule(&mut self, rule: &str) -> Result<(), JsValue> {
+        self.range_rule = Some(
+            rule.parse()
+                .map_err(|err: String| JsValue::from_str(&err))?,
+        );
+        Ok(())
+    }
+
+    /// Give each neighbor direction a weight, so that the rule is applied to
+    /// the sum of the weights of the live neighbors instead of their number.
+    /// `weights` lists the north-west, north, north-east, west, east,
+    /// south-west, south and south-east neighbors in that order. Sums below
+    /// 0 count as 0 and those above 255 as 255. A B/S rule has no section
+    /// for a sum above 8, so such cells die or stay dead, while a rule from
+    /// `set_range_rule` sees the sum itself. Weights of all 1 give the usual
+    /// count.
+    pub fn set_weights(&mut self, weights: &[i32]) -> Result<(), JsValue> {
+        let weights: [i32; 8] = weights.try_into().map_err(|_| {
+            JsValue::from_str(&format!(
+                "expected 8 neighbor weights, got {}",
+                weights.len()
+            ))
+        })?;
+
+        self.weights = if weights == [1; 8] {
+            None
+        } else {
+            Some(weights)
+        };
+        Ok(())
+    }
+
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// Set how many states a cell can be in, for Generations rules. With
+    /// more than 2, a live cell that doesn't survive passes through
+    /// `states - 2` dying states before it is dead. Dying cells don't count
+    /// as live neighbors and can't be born. Brian's Brain, for example, is
+    /// `B2/S` with 3 states.
+    ///
+    /// Cells that are currently dying are made dead.
+    pub fn set_states(&mut self, states: u8) -> Result<(), JsValue> {
+        if states < 2 {
+            return Err(JsValue::from_str(&format!(
+                "a cell needs at least 2 states, not {}",
+                states
+            )));
+        }
+
+        self.states = states;
+        self.dying = vec![0; self.cells.len()];
+        Ok(())
+    }
+
+    /// Set the chance, between 0 and 1, that a dead cell the rule brings to
+    /// life is actually born. Below 1 the simulation is no longer
+    /// deterministic: the same universe can evolve differently from run to
+    /// run. Survival isn't affected. Defaults to 1.
+    ///
+    /// The births are drawn from a generator seeded from the one behind
+    /// `seed_rng` when this is called.
+    pub fn set_birth_probability(&mut self, p: f64) {
+        self.birth_probability = p.clamp(0.0, 1.0);
+        self.rng = rng::with_default(|rng| Rng::new(rng.next_u64()));
+    }
+
+    /// The Generations state of a cell: 0 for dead, 1 for alive and 2 up to
+    /// `states - 1` for dying. Errors if the cell is outside the universe.
+    pub fn cell_state(&self, row: u32, column: u32) -> Result<u8, JsValue> {
+        let idx = self.checked_index(row, column)?;
+        Ok(match self.dying[idx] {
+            0 => self.cells.contains(idx) as u8,
+            state => state,
+        })
+    }
+
+    /// Play the Immigration Game: live cells belong to one of two species,
+    /// and a cell that is born takes the species most of its live neighbors
+    /// belong to. Which cells live and die is decided by the rule as usual.
+    /// Cells are the first species unless set otherwise with
+    /// `set_cell_species`, and turning this on makes every live cell the
+    /// first species again.
+    pub fn set_immigration_mode(&mut self, enabled: bool) {
+        if enabled && !self.immigration {
+            self.species = immigration::initial_species(&self.cells);
+        }
+        self.immigration = enabled;
+    }
+
+    /// Set the species of the live cell at `row` and `column` to 1 or 2.
+    pub fn set_cell_species(&mut self, row: u32, column: u32, species: u8) -> Result<(), JsValue> {
+        let idx = self.checked_index(row, column)?;
+        if species != immigration::FIRST_SPECIES && species != immigration::SECOND_SPECIES {
+            return Err(JsValue::from_str(&format!(
+                "species must be 1 or 2, not {}",
+                species
+            )));
+        }
+        if !self.cells.contains(idx) {
+            return Err(JsValue::from_str(&format!(
+                "cell ({}, {}) is dead and has no species",
+                row, column
+            )));
+        }
+
+        self.species[idx] = species;
+        Ok(())
+    }
+
+    /// Insert a pattern in run-length encoded (RLE) format, as found on the
+    /// LifeWiki, with its top-left corner at `row` and `column`. Returns an
+    /// error for a malformed pattern or one larger than the universe.
+    pub fn insert_rle(&mut self, row: u32, column: u32, rle: &str) -> Result<(), JsValue> {
+        let coords: Vec<(u32, u32)> = rle::decode(rle, self.height, self.width)
+            .map_err(|err| JsValue::from_str(&err))?
+            .iter()
+            .filter_map(|coord| {
+                self.insert_position(row as u64 + coord.0 as u64, column as u64 + coord.1 as u64)
+            })
+            .collect();
+        self.set_cells(&coords);
+        Ok(())
+    }
+
+    /// Insert a pattern in the `.cells` format found on the LifeWiki, with
+    /// its top-left corner at `row` and `column`, wrapping around the edges.
+    ///
+    /// This is the plaintext format: `!Name:` and other `!` lines are
+    /// comments, followed by rows of `.` (dead) and `O` (alive) cells.
+    pub fn insert_cells_format(
+        &mut self,
+        row: u32,
+        column: u32,
+        text: &str,
+    ) -> Result<(), JsValue> {
+        let grid = plaintext::decode(text).map_err(|err| JsValue::from_str(&err))?;
+        check_size(grid.height, grid.width)?;
+        let offsets: Vec<(i32, i32)> = grid
+            .cells
+            .iter()
+            .map(|&(d_row, d_col)| (d_row as i32, d_col as i32))
+            .collect();
+        self.insert_offsets(row, column, &offsets);
+        Ok(())
+    }
+
+    /// Export the live cells as a run-length encoded (RLE) pattern, trimmed
+    /// to their bounding box.
+    pub fn to_rle(&self) -> String {
+        let live_cells: Vec<(u32, u32)> = (0..self.height)
+            .flat_map(|row| (0..self.width).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.cells.contains(self.get_index(row, col)))
+            .collect();
+
+        rle::encode(&live_cells, &self.rule())
+    }
+
+    /// The universe as an ASCII PBM (`P1`) image with a pixel per cell, 1
+    /// for alive, which most image viewers open directly. Rows longer than
+    /// 70 cells are split over several lines, as the format asks.
+    pub fn to_pbm(&self) -> String {
+        let mut pbm = format!("P1\n{} {}\n", self.width, self.height);
+        for row in self.cells_vec().chunks(self.width as usize) {
+            for line in row.chunks(PBM_LINE_LEN) {
+                pbm.extend(line.iter().map(|&cell| if cell == 1 { '1' } else { '0' }));
+                pbm.push('\n');
+            }
+        }
+        pbm
+    }
+
+    /// Save the dimensions, generation and cells of the universe as a
+    /// compact binary blob.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(SERIALIZED_HEADER_LEN + self.cells.len().div_ceil(8));
+        bytes.extend_from_slice(SERIALIZED_MAGIC);
+        bytes.push(SERIALIZED_VERSION);
+        bytes.extend_from_slice(&self.width.to_le_bytes());
+        bytes.extend_from_slice(&self.height.to_le_bytes());
+        bytes.extend_from_slice(&self.generation.to_le_bytes());
+        bytes.extend_from_slice(&self.to_bits());
+        bytes
+    }
+
+    /// Restore a universe saved with `serialize`.
+    pub fn deserialize(bytes: &[u8]) -> Result<Universe, JsValue> {
+        Universe::from_bytes(bytes)
+    }
+
+    /// `serialize` as URL-safe base64 text, for sharing a universe in a
+    /// link. The dimensions are included, so `from_base64` needs nothing
+    /// else.
+    pub fn to_base64(&self) -> String {
+        base64::encode(&self.serialize())
+    }
+
+    /// Restore a universe saved with `to_base64`.
+    pub fn from_base64(s: &str) -> Result<Universe, JsValue> {
+        let bytes = base64::decode(s).map_err(|err| JsValue::from_str(&err))?;
+        Universe::deserialize(&bytes)
+    }
+
+    /// Save the dimensions, generation and cells of the universe as JSON,
+    /// such as `{"width":3,"height":1,"generation":0,"cells":[0,1,0]}`.
+    pub fn to_json(&self) -> String {
+        json::encode(&json::Snapshot {
+            width: self.width,
+            height: self.height,
+            generation: self.generation,
+            cells: self.cells_vec(),
+        })
+    }
+
+    /// Restore a universe saved with `to_json`.
+    pub fn from_json(s: &str) -> Result<Universe, JsValue> {
+        let snapshot = json::decode(s).map_err(|err| JsValue::from_str(&err))?;
+        check_size(snapshot.height, snapshot.width)?;
+
+        let cells = snapshot
+            .cells
+            .iter()
+            .map(|&cell| Cell::from(cell == 1))
+            .collect();
+
+        let mut universe = Universe::from_cells(snapshot.height, snapshot.width, cells);
+        universe.generation = snapshot.generation;
+        Ok(universe)
+    }
+
+    /// Create a universe from a pattern in the LifeWiki plaintext format,
+    /// sized to fit the pattern exactly.
+    pub fn from_plaintext(text: &str) -> Result<Universe, JsValue> {
+        let grid = plaintext::decode(text).map_err(|err| JsValue::from_str(&err))?;
+        check_size(grid.height, grid.width)?;
+
+        let mut cells = FixedBitSet::with_capacity(grid.width as usize * grid.height as usize);
+        for (row, col) in grid.cells {
+            cells.set(cell_index(grid.width, row, col), true);
+        }
+
+        Ok(Universe::from_cells(grid.height, grid.width, cells))
+    }
+
+    /// `live_bounds` for JavaScript, as a `[min_row, min_col, max_row,
+    /// max_col]` array or `undefined` if nothing is alive.
+    #[wasm_bindgen(js_name = live_bounds)]
+    pub fn live_bounds_js(&self) -> Option<Vec<u32>> {
+        self.live_bounds()
+            .map(|(min_row, min_col, max_row, max_col)| vec![min_row, min_col, max_row, max_col])
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Set the width of the universe.
+    ///
+    /// Resets all cells to the dead cell state and the generation to 0.
+    /// Returns an error for a size the universe couldn't be created with.
+    pub fn set_width(&mut self, width: u32) -> Result<(), JsValue> {
+        check_size(self.height, width)?;
+
+        self.width = width;
+        self.cells = FixedBitSet::with_capacity(self.width as usize * self.height as usize);
+        self.buffer_cells = self.cells.clone();
+        self.ages = vec![0; self.cells.len()];
+        self.dying = vec![0; self.cells.len()];
+        self.species = vec![0; self.cells.len()];
+        self.generation = 0;
+        self.live_count = 0;
+        self.history.clear();
+        self.changed_cells.clear();
+        Ok(())
+    }
+
+    /// Change the width of the universe, keeping every cell in the same
+    /// row-major order. Unlike `resize`, which crops, this reflows the cells
+    /// like text: cell `idx` stays cell `idx`, so rows that are now too long
+    /// continue on the next one. The height grows or shrinks to just fit
+    /// every cell, and the last row is padded with dead cells. Returns an
+    /// error for a size the universe couldn't be created with.
+    pub fn set_width_preserving(&mut self, width: u32) -> Result<(), JsValue> {
+        let len = self.cells.len();
+        let height = len.div_ceil(width.max(1) as usize) as u32;
+        check_size(height, width)?;
+
+        let mut cells = FixedBitSet::with_capacity(width as usize * height as usize);
+        for idx in self.cells.ones() {
+            cells.set(idx, true);
+        }
+
+        self.width = width;
+        self.height = height;
+        self.replace_cells(cells);
+        self.history.clear();
+        self.changed_cells.clear();
+        Ok(())
+    }
+
+    /// Resize the universe, keeping the cells that still fit within the new
+    /// dimensions and filling any new space with dead cells. Returns an
+    /// error for a size the universe couldn't be created with.
+    pub fn resize(&mut self, new_height: u32, new_width: u32) -> Result<(), JsValue> {
+        check_size(new_height, new_width)?;
+
+        let cells: FixedBitSet = (0..new_height)
+            .flat_map(|row| (0..new_width).map(move |col| (row, col)))
+            .map(|(row, col)| {
+                let alive = row < self.height
+                    && col < self.width
+                    && self.cells.contains(self.get_index(row, col));
+                Cell::from(alive)
+            })
+            .collect();
+
+        self.width = new_width;
+        self.height = new_height;
+        self.replace_cells(cells);
+        self.history.clear();
+        self.changed_cells.clear();
+        Ok(())
+    }
+
+    /// The cells packed one bit per cell, 8 cells per byte, in the layout
+    /// `set_from_bits` reads. Bits past the last cell are 0. This is about
+    /// an eighth the size of `cells_vec`.
+    pub fn to_bits(&self) -> Vec<u8> {
+        self.cells.to_bytes()
+    }
+
+    /// Replace the whole universe with a `height` by `width` board packed
+    /// one bit per cell, 8 cells per byte. Cells are in row-major order,
+    /// starting from the least significant bit of the first byte, and
+    /// `bits` must hold exactly `ceil(width * height / 8)` bytes. The
+    /// generation is kept.
+    pub fn set_from_bits(&mut self, bits: &[u8], width: u32, height: u32) -> Result<(), JsValue> {
+        check_size(height, width)?;
+
+        let cell_count = width as usize * height as usize;
+        let cells = FixedBitSet::from_bytes(bits, cell_count).ok_or_else(|| {
+            JsValue::from_str(&format!(
+                "a {}x{} board needs {} bytes of bits, got {}",
+                height,
+                width,
+                cell_count.div_ceil(8),
+                bits.len()
+            ))
+        })?;
+
+        self.width = width;
+        self.height = height;
+        self.replace_cells(cells);
+        self.history.clear();
+        self.changed_cells.clear();
+        Ok(())
+    }
+
+    /// Rotate the universe a quarter turn clockwise, swapping its width and
+    /// height.
+    pub fn rotate_cw(&mut self) {
+        let height = self.height;
+        self.remap(self.width, self.height, |row, col| (height - 1 - col, row));
+    }
+
+    /// Rotate the universe a quarter turn counterclockwise, swapping its
+    /// width and height.
+    pub fn rotate_ccw(&mut self) {
+        let width = self.width;
+        self.remap(self.width, self.height, |row, col| (col, width - 1 - row));
+    }
+
+    /// Mirror the universe left to right.
+    pub fn flip_horizontal(&mut self) {
+        let width = self.width;
+        self.remap(self.height, self.width, |row, col| (row, width - 1 - col));
+    }
+
+    /// Mirror the universe top to bottom.
+    pub fn flip_vertical(&mut self) {
+        let height = self.height;
+        self.remap(self.height, self.width, |row, col| (height - 1 - row, col));
+    }
+
+    /// Move every cell `d_row` rows down and `d_col` columns right, wrapping
+    /// around the edges. Negative offsets move up and left.
+    pub fn shift(&mut self, d_row: i32, d_col: i32) {
+        let (height, width) = (self.height, self.width);
+        // Each cell comes from the opposite offset, reduced first so that
+        // negating it can't overflow.
+        let up = -((d_row as i64).rem_euclid(height as i64) as i32);
+        let left = -((d_col as i64).rem_euclid(width as i64) as i32);
+        self.remap(height, width, |row, col| {
+            (wrap_coord(row, up, height), wrap_coord(col, left, width))
+        });
+    }
+
+    /// Shift every cell so that the middle of the live cells' bounding box
+    /// lands in the middle of the universe, wrapping around the edges. Does
+    /// nothing if no cell is alive.
+    pub fn center_live_cells(&mut self) {
+        let (min_row, min_col, max_row, max_col) = match self.live_bounds() {
+            Some(bounds) => bounds,
+            None => return,
+        };
+
+        let d_row = (self.height - 1) as i64 / 2 - (min_row + max_row) as i64 / 2;
+        let d_col = (self.width - 1) as i64 / 2 - (min_col + max_col) as i64 / 2;
+        self.shift(d_row as i32, d_col as i32);
+    }
+
+    /// Set whether patterns inserted across an edge wrap around to the
+    /// opposite side, as they do by default, or are cut off at the edge.
+    /// This applies to `set_cells`, `stamp`, `paste` and every `insert_*`
+    /// method, and is independent of the boundary mode the universe evolves
+    /// by.
+    pub fn set_insert_wrap(&mut self, enabled: bool) {
+        self.insert_wrap = enabled;
+    }
+
+    /// Grow the universe by 16 rows or columns past an edge whenever a live
+    /// cell touches it at the start of a generation, so spaceships keep
+    /// flying instead of wrapping around or hitting the boundary. The cells
+    /// keep their place relative to each other, but row and column numbers
+    /// shift when the top or left edge grows. Growth stops at the size
+    /// `set_max_cells` allows. Off by default.
+    pub fn set_auto_expand(&mut self, enabled: bool) {
+        self.auto_expand = enabled;
+    }
+
+    /// Set how many previous generations are kept for `step_back`.
+    ///
+    /// Defaults to 0, which disables the history.
+    pub fn set_history_capacity(&mut self, capacity: usize) {
+        self.history_capacity = capacity;
+        while self.history.len() > capacity {
+            self.history.pop_front();
+        }
+    }
+
+    /// Record the live count after every generation in
+    /// `population_history`, for plotting the population over time.
+    /// Turning this off discards the history.
+    pub fn set_track_population(&mut self, enabled: bool) {
+        self.track_population = enabled;
+        if !enabled {
+            self.population_history.clear();
+        }
+    }
+
+    /// Keep only the last `limit` entries of the population history, or
+    /// every entry if `limit` is 0, the default.
+    pub fn set_population_limit(&mut self, limit: usize) {
+        self.population_limit = limit;
+        if limit > 0 {
+            while self.population_history.len() > limit {
+                self.population_history.pop_front();
+            }
+        }
+    }
+
+    /// Start recording the state of the cell at `row` and `column` after
+    /// every generation, for following a single cell of an oscillator. Only
+    /// one cell is watched at a time, so this replaces any earlier one and
+    /// restarts the history with the cell's current state.
+    pub fn watch_cell(&mut self, row: u32, column: u32) -> Result<(), JsValue> {
+        self.checked_index(row, column)?;
+        self.watched_cell = Some((row, column));
+        self.cell_history.clear();
+        self.record_watched_cell();
+        Ok(())
+    }
+
+    /// The states of the cell registered with `watch_cell`, 1 for alive and
+    /// 0 for dead, from when it was registered up to the current generation.
+    /// `tick_hashed` only adds the state after its last generation.
+    pub fn cell_history(&self) -> Vec<u8> {
+        self.cell_history.clone()
+    }
+
+    /// The live count after each generation since tracking was turned on
+    /// with `set_track_population`, oldest first. `tick_hashed` only adds
+    /// the count after its last generation.
+    pub fn population_history(&self) -> Vec<u32> {
+        self.population_history.iter().copied().collect()
+    }
+
+    /// Restore the generation before the last `tick` from the history.
+    ///
+    /// Returns `false` without changing anything when there is no history
+    /// left to restore.
+    pub fn step_back(&mut self) -> bool {
+        let cells = match self.history.pop_back() {
+            Some(cells) => cells,
+            None => return false,
+        };
+
+        self.replace_cells(cells);
+        self.generation -= 1;
+        self.population_history.pop_back();
+        true
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Set the height of the universe.
+    ///
+    /// Resets all cells to the dead cell state and the generation to 0.
+    /// Returns an error for a size the universe couldn't be created with.
+    pub fn set_height(&mut self, height: u32) -> Result<(), JsValue> {
+        check_size(height, self.width)?;
+
+        self.height = height;
+        self.cells = FixedBitSet::with_capacity(self.width as usize * self.height as usize);
+        self.buffer_cells = self.cells.clone();
+        self.ages = vec![0; self.cells.len()];
+        self.dying = vec![0; self.cells.len()];
+        self.species = vec![0; self.cells.len()];
+        self.generation = 0;
+        self.live_count = 0;
+        self.history.clear();
+        self.changed_cells.clear();
+        Ok(())
+    }
+
+    /// The number of times the universe has ticked since it was created.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// The number of live cells, kept up to date as the universe changes.
+    pub fn live_count(&self) -> u32 {
+        self.live_count
+    }
+
+    /// Search for a universe that evolves into this one in a single tick,
+    /// with the same size and settings.
+    ///
+    /// Predecessors aren't unique, so this is just one of them. `None` means
+    /// there is none, making this a Garden of Eden, or that the universe has
+    /// more than 36 cells, beyond which the search takes too long. Ranges
+    /// above 1 aren't searched either.
+    pub fn find_predecessor(&self) -> Option<Universe> {
+        let cells = self.predecessor()?;
+
+        let mut predecessor = Universe::from_cells(self.height, self.width, cells);
+        predecessor.boundaries = self.boundaries;
+        predecessor.neighborhood = self.neighborhood;
+        predecessor.rule = self.rule;
+        predecessor.range = self.range;
+        predecessor.range_rule = self.range_rule.clone();
+        predecessor.weights = self.weights;
+        Some(predecessor)
+    }
+
+    /// A new universe holding a copy of the `height` by `width` block of
+    /// cells whose top-left cell is at `top` and `left`, with the same rule,
+    /// boundary modes and neighborhood. Parts of the block past the edges
+    /// wrap around to the opposite side if `wrap` is set, and are dead
+    /// otherwise.
+    pub fn subgrid(
+        &self,
+        top: u32,
+        left: u32,
+        height: u32,
+        width: u32,
+        wrap: bool,
+    ) -> Result<Universe, JsValue> {
+        check_size(height, width)?;
+
+        let cells = (0..height as u64)
+            .flat_map(|row| (0..width as u64).map(move |col| (row, col)))
+            .map(|(row, col)| {
+                let (row, col) = (top as u64 + row, left as u64 + col);
+                let alive = if wrap {
+                    let row = (row % self.height as u64) as u32;
+                    let col = (col % self.width as u64) as u32;
+                    self.cells.contains(self.get_index(row, col))
+                } else {
+                    row < self.height as u64
+                        && col < self.width as u64
+                        && self.cells.contains(self.get_index(row as u32, col as u32))
+                };
+                Cell::from(alive)
+            })
+            .collect();
+
+        let mut subgrid = Universe::from_cells(height, width, cells);
+        subgrid.boundaries = self.boundaries;
+        subgrid.neighborhood = self.neighborhood;
+        subgrid.rule = self.rule;
+        subgrid.range = self.range;
+        subgrid.range_rule = self.range_rule.clone();
+        subgrid.weights = self.weights;
+        Ok(subgrid)
+    }
+
+    /// Whether `other` has the same dimensions and the same live cells. The
+    /// generation, rule and other settings aren't compared.
+    pub fn equals(&self, other: &Universe) -> bool {
+        self.width == other.width && self.height == other.height && self.cells == other.cells
+    }
+
+    /// The live count, size, density and generation in a single call.
+    pub fn stats(&self) -> Stats {
+        let total_cells = self.cells.len() as u32;
+        Stats {
+            live_count: self.live_count,
+            total_cells,
+            density: self.live_count as f64 / total_cells as f64,
+            generation: self.generation,
+        }
+    }
+
+    /// The width, height, generation and live count in a single call.
+    pub fn info(&self) -> Info {
+        Info {
+            width: self.width,
+            height: self.height,
+            generation: self.generation,
+            population: self.live_count,
+        }
+    }
+
+    /// The indices of the live cells in ascending order. Cell `idx` is at
+    /// row `idx / width` and column `idx % width`.
+    pub fn live_cells(&self) -> Vec<u32> {
+        self.cells.ones().map(|idx| idx as u32).collect()
+    }
+
+    /// The indices of the cells that changed state in the last tick, so
+    /// only those need to be redrawn.
+    pub fn changed_cells(&self) -> Vec<u32> {
+        self.changed_cells.clone()
+    }
+
+    /// How many cells were born or died in the last tick: the Hamming
+    /// distance between the last two generations. Chaotic universes have a
+    /// high activity, and settled ones an activity of 0. After `tick_hashed`
+    /// it is the distance across every generation it advanced.
+    pub fn activity(&self) -> u32 {
+        self.activity
+    }
+
+    /// Whether the last tick left every cell unchanged, meaning the universe
+    /// will stay the same forever.
+    pub fn is_stable(&self) -> bool {
+        self.stable
+    }
+
+    /// Call `callback` whenever a tick leaves the universe stable after it
+    /// wasn't before.
+    pub fn set_on_stable(&mut self, callback: js_sys::Function) {
+        self.on_stable = Some(callback);
+    }
+
+    /// Time every tick with `console.time` in the browser's devtools.
+    ///
+    /// Off by default.
+    pub fn set_profiling(&mut self, enabled: bool) {
+        self.profiling = enabled;
+    }
+
+    /// The flat indices, in ascending order, of the cells connected to the
+    /// one at `row` and `column` through cells in the same state, like a
+    /// paint bucket fill. Cells touching diagonally are connected, as are
+    /// cells across edges that wrap around.
+    pub fn connected_region(&self, row: u32, column: u32) -> Result<Vec<u32>, JsValue> {
+        let start = self.checked_index(row, column)?;
+        let alive = self.cells.contains(start);
+
+        let mut region = FixedBitSet::with_capacity(self.cells.len());
+        region.set(start, true);
+        let mut stack = vec![start];
+        while let Some(idx) = stack.pop() {
+            for touching in self.touching_cells(idx) {
+                if self.cells.contains(touching) == alive && !region.contains(touching) {
+                    region.set(touching, true);
+                    stack.push(touching);
+                }
+            }
+        }
+
+        Ok(region.ones().map(|idx| idx as u32).collect())
+    }
+
+    /// The number of separate objects: groups of live cells connected
+    /// through cells touching orthogonally or diagonally, including across
+    /// edges that wrap around, like `connected_region`.
+    pub fn object_count(&self) -> u32 {
+        let mut seen = FixedBitSet::with_capacity(self.cells.len());
+        let mut objects = 0;
+        for start in self.cells.ones() {
+            if seen.contains(start) {
+                continue;
+            }
+
+            objects += 1;
+            seen.set(start, true);
+            let mut stack = vec![start];
+            while let Some(idx) = stack.pop() {
+                for touching in self.touching_cells(idx) {
+                    if self.cells.contains(touching) && !seen.contains(touching) {
+                        seen.set(touching, true);
+                        stack.push(touching);
+                    }
+                }
+            }
+        }
+
+        objects
+    }
+
+    /// The number of still lifes: groups of touching live cells that stay
+    /// exactly the same in the next generation, with no cells born next to
+    /// them. A block and a beehive count as two, however many cells they
+    /// have. Objects touching an oscillator form one group with it and so
+    /// aren't counted. The universe itself isn't changed.
+    pub fn still_lifes(&self) -> u32 {
+        let candidates: Vec<usize> = match self.active_cells() {
+            Some(active) => active.ones().collect(),
+            None => (0..self.cells.len()).collect(),
+        };
+        let mut changed = FixedBitSet::with_capacity(self.cells.len());
+        for idx in candidates {
+            if self.next_cell(idx) != Cell::from(self.cells.contains(idx)) {
+                changed.set(idx, true);
+            }
+        }
+
+        let mut seen = FixedBitSet::with_capacity(self.cells.len());
+        let mut still_lifes = 0;
+        for start in self.cells.ones() {
+            if seen.contains(start) {
+                continue;
+            }
+
+            // Flood fill the group, noting whether it or any cell around it
+            // changes.
+            seen.set(start, true);
+            let mut stack = vec![start];
+            let mut still = true;
+            while let Some(idx) = stack.pop() {
+                still &= !changed.contains(idx);
+                for &neighbor in self.neighbor_indices(idx).iter().flatten() {
+                    still &= !changed.contains(neighbor);
+                    if self.cells.contains(neighbor) && !seen.contains(neighbor) {
+                        seen.set(neighbor, true);
+                        stack.push(neighbor);
+                    }
+                }
+            }
+
+            if still {
+                still_lifes += 1;
+            }
+        }
+
+        still_lifes
+    }
+
+    /// The smallest number of generations, up to `max_period`, after which
+    /// the universe returns to its current state, or `None` if it doesn't.
+    ///
+    /// A still life has a period of 1. The universe is left exactly as it
+    /// was, and the stable callback isn't called while looking ahead.
+    pub fn detect_period(&mut self, max_period: u32) -> Option<u32> {
+        self.look_ahead(|universe| {
+            let start = universe.cells.clone();
+            let start_hash = start.hash();
+            for step in 1..=max_period {
+                universe.step_once();
+                // Compare the cells as well so a hash collision can't report
+                // a bogus period.
+                if universe.cells.hash() == start_hash && universe.cells == start {
+                    return Some(step);
+                }
+            }
+            None
+        })
+    }
+
+    /// How many generations, up to `cap`, the universe stays active before
+    /// it settles into a still life or an oscillator, like the lifespan of
+    /// a Methuselah. A universe that already repeats has a lifespan of 0,
+    /// and `cap` means it didn't settle in time.
+    ///
+    /// Generations are recognized by their hash, so a collision could end
+    /// the search early, though that is very unlikely. The universe is left
+    /// exactly as it was.
+    pub fn lifespan(&mut self, cap: u32) -> u32 {
+        self.look_ahead(|universe| {
+            let mut seen = HashMap::new();
+            seen.insert(universe.cells.hash(), 0);
+            for step in 1..=cap {
+                universe.step_once();
+                if let Some(&first) = seen.get(&universe.cells.hash()) {
+                    return first;
+                }
+                seen.insert(universe.cells.hash(), step);
+            }
+            cap
+        })
+    }
+
+    /// A pointer to one byte per cell, rebuilt from the packed cells on
+    /// every call. Prefer `cells_bits` when rendering every frame.
+    ///
+    /// View it from JavaScript with
+    /// `new Uint8Array(memory.buffer, universe.cells(), universe.cells_len())`.
+    pub fn cells(&self) -> *const Cell {
+        let mut cell_bytes = self.cell_bytes.borrow_mut();
+        *cell_bytes = self.get_cells();
+        cell_bytes.as_ptr()
+    }
+
+    /// The next generation as one byte per cell, 1 for alive and 0 for dead,
+    /// without changing the universe. With a birth probability below 1
+    /// every birth the rule allows is shown, since which ones happen is only
+    /// decided by the tick itself.
+    pub fn preview_next(&self) -> Vec<u8> {
+        (0..self.cells.len())
+            .map(|idx| (self.next_cell(idx) == Cell::Alive) as u8)
+            .collect()
+    }
+
+    /// The number of cells, which is the length of the buffer behind `cells`
+    /// in bytes.
+    pub fn cells_len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// A copy of the cells, one byte per cell that is 1 for alive and 0 for
+    /// dead. Unlike `cells` this arrives in JavaScript as a `Uint8Array` of
+    /// the right length that stays valid, at the cost of a copy.
+    pub fn cells_vec(&self) -> Vec<u8> {
+        (0..self.cells.len())
+            .map(|idx| self.cells.contains(idx) as u8)
+            .collect()
+    }
+
+    /// The cells as an array of `height` rows, each a `Uint8Array` of
+    /// `width` cells that are 1 for alive and 0 for dead.
+    pub fn rows(&self) -> js_sys::Array {
+        let cells = self.cells_vec();
+        cells
+            .chunks(self.width as usize)
+            .map(|row| JsValue::from(js_sys::Uint8Array::from(row)))
+            .collect()
+    }
+
+    /// A pointer to the packed cells, one bit per cell. Cell `idx` is alive
+    /// when bit `idx % 32` of the `idx / 32`th `u32` is set.
+    pub fn cells_bits(&self) -> *const u32 {
+        self.cells.as_slice().as_ptr() as *const u32
+    }
+
+    /// A pointer to one `u32` per cell holding how many generations it has
+    /// been continuously alive. Dead cells and cells born in the last tick
+    /// have an age of 0.
+    pub fn ages(&self) -> *const u32 {
+        self.ages.as_ptr()
+    }
+
+    /// A pointer to one byte per cell holding its species in immigration
+    /// mode: 1 or 2 for live cells and 0 for dead ones.
+    pub fn species(&self) -> *const u8 {
+        self.species.as_ptr()
+    }
+
+    pub fn render(&self) -> String {
+        self.to_string()
+    }
+
+    /// Render the universe like `render`, using custom symbols for dead and
+    /// alive cells.
+    pub fn render_with(&self, dead: char, alive: char) -> String {
+        let mut rendered = String::new();
+        self.write_grid(&mut rendered, dead, alive)
+            .expect("writing to a String cannot fail");
+        rendered
+    }
+
+    /// Render the universe like `render`, framed by box drawing characters
+    /// so the edges stand out: where a toroidal universe wraps around, or
+    /// where a dead boundary cuts patterns off.
+    pub fn render_bordered(&self) -> String {
+        let horizontal = "─".repeat(self.width as usize);
+        let mut rendered = format!("┌{}┐\n", horizontal);
+        for line in self.to_string().lines() {
+            rendered.push_str(&format!("│{}│\n", line));
+        }
+        rendered.push_str(&format!("└{}┘\n", horizontal));
+        rendered
+    }
+
+    /// `to_rgba` for JavaScript, with colors given as `0xRRGGBBAA`.
+    #[wasm_bindgen(js_name = to_rgba)]
+    pub fn to_rgba_js(&self, alive: u32, dead: u32) -> Vec<u8> {
+        self.to_rgba(alive.to_be_bytes(), dead.to_be_bytes())
+    }
+
+    pub fn tick(&mut self) {
+        let _timer = self.timer("Universe::tick");
+
+        {
+            let _timer = self.timer("new generation");
+            self.step_once();
+        }
+
+        let _timer = self.timer("free old cells");
+    }
+
+    /// Keep ticking until `budget_ms` milliseconds have passed, returning how
+    /// many generations were run. The budget is checked before every tick,
+    /// so the last one may overrun it.
+    pub fn tick_within(&mut self, budget_ms: f64) -> u32 {
+        let _timer = self.timer("Universe::tick_within");
+
+        self.tick_within_clock(budget_ms, js_sys::Date::now)
+    }
+
+    /// Advance the universe by a single generation like `tick`, returning
+    /// how many cells changed state. Once this returns 0 the universe is
+    /// stable and the animation loop can stop.
+    pub fn tick_returning_changes(&mut self) -> u32 {
+        self.tick();
+        self.changed_cells.len() as u32
+    }
+
+    /// Advance the universe by a single generation like `tick`, returning
+    /// a byte per cell in row-major order that is 1 if the cell was born, -1
+    /// if it died and 0 if it stayed the same, so each event can be played
+    /// as a sound. Dying cells moving through the Generations states aren't
+    /// events.
+    pub fn tick_events(&mut self) -> Vec<i8> {
+        let before = self.cells.clone();
+        self.tick();
+
+        let mut events = vec![0; self.cells.len()];
+        for &idx in &self.changed_cells {
+            let idx = idx as usize;
+            events[idx] = self.cells.contains(idx) as i8 - before.contains(idx) as i8;
+        }
+        events
+    }
+
+    /// Advance the universe by a single generation like `tick`, only
+    /// evaluating cells next to ones that changed in the last generation.
+    /// This is much faster on sparse universes and gives the same results.
+    pub fn tick_incremental(&mut self) {
+        let _timer = self.timer("Universe::tick_incremental");
+
+        self.step_incremental();
+    }
+
+    /// Advance the universe by a single generation like `tick`, looking up
+    /// each cell's next state in a precomputed table of every 3x3 window
+    /// rather than counting its neighbors. This pays off on dense universes,
+    /// where most cells have to be visited anyway, and gives the same
+    /// results.
+    pub fn tick_lut(&mut self) {
+        let _timer = self.timer("Universe::tick_lut");
+
+        self.step_lut();
+    }
+
+    /// Run `steps` generations in one call, without timing each of them.
+    pub fn tick_n(&mut self, steps: u32) {
+        let _timer = self.timer("Universe::tick_n");
+
+        for _ in 0..steps {
+            self.step_once();
+        }
+    }
+
+    /// Run `gens` generations and return whether any cell is still alive,
+    /// for searching random soups. The universe is left at the last
+    /// generation.
+    pub fn survives(&mut self, gens: u32) -> bool {
+        let _timer = self.timer("Universe::survives");
+
+        for _ in 0..gens {
+            self.step_once();
+        }
+
+        self.live_count > 0
+    }
+
+    /// Run `gens` generations and count the distinct gliders seen flying
+    /// off on their own, such as the ones a glider gun fires. The universe
+    /// is left at the last generation.
+    ///
+    /// This is a heuristic. A glider is recognized by its shape in any phase
+    /// and direction, once nothing else is alive within two cells of it, and
+    /// is followed from one generation to the next by its position. Gliders
+    /// that never get clear of other cells, such as ones fired into a crowd,
+    /// aren't counted, and one that is lost for more than a few generations
+    /// is counted again when it turns up. Only Conway's rules have gliders
+    /// of this shape.
+    pub fn escaping_gliders(&mut self, gens: u32) -> u32 {
+        let _timer = self.timer("Universe::escaping_gliders");
+
+        self.count_escaping_gliders(gens)
+    }
+
+    /// Run up to `max_gens` generations, stopping early once the universe is
+    /// stable. Returns how many generations were run.
+    ///
+    /// `cb` is called with the generation and live count every 64
+    /// generations and once more when the run ends, so long runs can report
+    /// progress.
+    pub fn run_until(&mut self, max_gens: u32, cb: js_sys::Function) -> u32 {
+        let _timer = self.timer("Universe::run_until");
+
+        let report = |universe: &Universe| {
+            // An exception in the callback shouldn't stop the run.
+            let _ = cb.call2(
+                &JsValue::NULL,
+                &JsValue::from(universe.generation as f64),
+                &JsValue::from(universe.live_count),
+            );
+        };
+
+        let mut run = 0;
+        while run < max_gens {
+            self.step_once();
+            run += 1;
+
+            if self.stable || run == max_gens {
+                break;
+            }
+            if run.is_multiple_of(PROGRESS_INTERVAL) {
+                report(self);
+            }
+        }
+
+        report(self);
+        run
+    }
+
+    /// Run up to `gens_cap` generations, stopping at the first one that
+    /// changes a different number of cells than the generation before it,
+    /// such as a glider running into something after flying steadily.
+    /// Returns how many generations were run.
+    ///
+    /// The last tick before the call is the first one compared against. A
+    /// universe that hasn't ticked yet has nothing to compare, so its first
+    /// generation is run to start from.
+    pub fn run_until_change_delta(&mut self, gens_cap: u32) -> u32 {
+        let _timer = self.timer("Universe::run_until_change_delta");
+
+        let mut previous = if self.generation > 0 {
+            Some(self.activity)
+        } else {
+            None
+        };
+
+        let mut run = 0;
+        while run < gens_cap {
+            self.step_once();
+            run += 1;
+
+            if previous.is_some_and(|previous| previous != self.activity) {
+                break;
+            }
+            previous = Some(self.activity);
+        }
+        run
+    }
+
+    /// Advance the universe by `steps` generations using HashLife, which
+    /// caches how every block of cells evolves. Large universes built from
+    /// repeated structures advance many generations far faster than with
+    /// `tick_n`, with the same results.
+    ///
+    /// The generations are skipped over rather than visited one by one, so
+    /// `changed_cells` covers the whole jump, cell ages start over, the
+    /// history is cleared and the stable callback isn't called.
+    pub fn tick_hashed(&mut self, steps: u32) {
+        let _timer = self.timer("Universe::tick_hashed");
+
+        self.step_hashed(steps);
+    }
+
+    /// Advance the universe by a single generation like `tick`. With the
+    /// `parallel` feature the rows are split across threads where the target
+    /// can spawn them, which pays off on large universes; otherwise this is
+    /// the same as `tick`.
+    pub fn tick_parallel(&mut self) {
+        let _timer = self.timer("Universe::tick_parallel");
+
+        #[cfg(feature = "parallel")]
+        if !self.is_plain() {
+            self.step_once();
+        } else {
+            self.step_parallel();
+        }
+        #[cfg(not(feature = "parallel"))]
+        self.step_once();
+    }
+}
 
-        let _timer = Timer::new("free old cells");
+#[cfg(test)]
+mod tests {
+    use super::cell_index;
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn cell_index_does_not_overflow_u32() {
+        // 100000 * 100000 cells is more than fits in a u32.
+        assert_eq!(cell_index(100_000, 99_999, 99_999), 9_999_999_999);
+        assert_eq!(cell_index(100_000, 50_000, 3), 5_000_000_003);
+        assert_eq!(cell_index(8, 2, 3), 19);
     }
 }
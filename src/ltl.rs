@@ -0,0 +1,138 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::bitset::FixedBitSet;
+use crate::{Cell, Neighborhood, Universe};
+
+/// The largest neighborhood range `Universe::set_range` accepts. A range of
+/// 7 has 224 neighbors, the most that still fit the `u8` counts.
+pub const MAX_RANGE: u32 = 7;
+
+/// A Larger than Life rule, where a dead cell is born when its live neighbor
+/// count falls in `birth` and a live cell survives when it falls in
+/// `survival`. Bosco's Rule, for example, is `B34..45/S33..57` with a range
+/// of 5.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeRule {
+    birth: RangeInclusive<u8>,
+    survival: RangeInclusive<u8>,
+}
+
+impl RangeRule {
+    /// The state of a cell in the next generation.
+    pub fn next_cell(&self, cell: Cell, live_neighbors: u8) -> Cell {
+        let range = match cell {
+            Cell::Alive => &self.survival,
+            Cell::Dead => &self.birth,
+        };
+
+        Cell::from(range.contains(&live_neighbors))
+    }
+}
+
+/// Parse the counts following a `B` or `S` prefix, either a single count or
+/// an inclusive `min..max` range.
+fn parse_range(counts: &str) -> Result<RangeInclusive<u8>, String> {
+    let parse = |count: &str| {
+        count
+            .parse::<u8>()
+            .map_err(|_| format!("invalid neighbor count '{}' in rule", count))
+    };
+
+    let (min, max) = match counts.split_once("..") {
+        Some((min, max)) => (parse(min)?, parse(max)?),
+        None => {
+            let count = parse(counts)?;
+            (count, count)
+        }
+    };
+
+    if min > max {
+        return Err(format!("empty neighbor count range '{}' in rule", counts));
+    }
+
+    Ok(min..=max)
+}
+
+impl FromStr for RangeRule {
+    type Err = String;
+
+    fn from_str(rule: &str) -> Result<RangeRule, String> {
+        let mut birth = None;
+        let mut survival = None;
+
+        for part in rule.trim().split('/') {
+            let mut chars = part.chars();
+            let target = match chars.next() {
+                Some('B') | Some('b') => &mut birth,
+                Some('S') | Some('s') => &mut survival,
+                _ => return Err(format!("expected a B or S section in rule '{}'", rule)),
+            };
+
+            if target.is_some() {
+                return Err(format!("duplicate section '{}' in rule '{}'", part, rule));
+            }
+
+            *target = Some(parse_range(chars.as_str())?);
+        }
+
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(RangeRule { birth, survival }),
+            _ => Err(format!("rule '{}' needs both a B and an S section", rule)),
+        }
+    }
+}
+
+impl fmt::Display for RangeRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "B{}..{}/S{}..{}",
+            self.birth.start(),
+            self.birth.end(),
+            self.survival.start(),
+            self.survival.end()
+        )
+    }
+}
+
+impl Universe {
+    /// The indices of every neighbor of a cell within `range`, for ranges
+    /// above 1: the cells at a Chebyshev distance of at most `range` in the
+    /// Moore neighborhood, or a Manhattan distance in the von Neumann one.
+    /// Neighbors off a dead edge are left out, and on a small toroidal
+    /// universe the same cell can be reached more than once.
+    pub(crate) fn range_neighbors(&self, idx: usize) -> Vec<usize> {
+        let row = idx as u32 / self.width;
+        let column = idx as u32 % self.width;
+        let range = self.range as i64;
+
+        let mut neighbors = Vec::new();
+        for d_row in -range..=range {
+            for d_col in -range..=range {
+                if (d_row, d_col) == (0, 0)
+                    || (self.neighborhood == Neighborhood::VonNeumann
+                        && d_row.abs() + d_col.abs() > range)
+                {
+                    continue;
+                }
+
+                let row = self.boundaries.y.offset(row, d_row, self.height);
+                let column = self.boundaries.x.offset(column, d_col, self.width);
+                if let (Some(row), Some(column)) = (row, column) {
+                    neighbors.push(self.get_index(row, column));
+                }
+            }
+        }
+        neighbors
+    }
+
+    /// The number of live neighbors of a cell in `cells` within `range`.
+    pub(crate) fn range_count(&self, cells: &FixedBitSet, row: u32, column: u32) -> u8 {
+        self.range_neighbors(self.get_index(row, column))
+            .into_iter()
+            .filter(|&neighbor| cells.contains(neighbor))
+            .count() as u8
+    }
+}
@@ -0,0 +1,98 @@
+use crate::rule::Rule;
+use crate::{cell_index, Cell, Neighborhood, Universe};
+
+/// Bit `n` of a window is set when the cell at position `n` of a 3x3 block
+/// is alive. The columns are stacked west to east, 3 bits each, with the
+/// north cell of each column in its highest bit, so the center is bit 4.
+const CENTER: u16 = 1 << 4;
+
+/// The bits of a window that are neighbors in the von Neumann neighborhood:
+/// the west, north, south and east cells.
+const VON_NEUMANN: u16 = 1 << 7 | 1 << 5 | 1 << 3 | 1 << 1;
+
+/// The next state of the center cell for each of the 512 possible 3x3
+/// windows, 1 for alive.
+fn transition_table(rule: Rule, neighborhood: Neighborhood) -> [u8; 512] {
+    let neighbors = match neighborhood {
+        Neighborhood::Moore => 0x1FF & !CENTER,
+        Neighborhood::VonNeumann => VON_NEUMANN,
+    };
+
+    let mut table = [0; 512];
+    for (window, next) in table.iter_mut().enumerate() {
+        let window = window as u16;
+        let cell = Cell::from(window & CENTER != 0);
+        let live_neighbors = (window & neighbors).count_ones() as u8;
+        *next = (rule.next_cell(cell, live_neighbors) == Cell::Alive) as u8;
+    }
+    table
+}
+
+impl Universe {
+    /// Advance the universe by a single generation like `step_once`, looking
+    /// up each cell's next state in a table indexed by its 3x3 window
+    /// instead of counting its neighbors.
+    ///
+    /// Every row is first packed into 3 bits per column holding the cells
+    /// above, on and below it, so each window is just three of those put
+    /// together. Anything the table can't express falls back to
+    /// `step_once`.
+    pub(crate) fn step_lut(&mut self) {
+        if !self.is_plain() {
+            self.step_once();
+            return;
+        }
+
+        self.push_history();
+
+        let table = transition_table(self.rule, self.neighborhood);
+        let (width, height) = (self.width, self.height);
+        let alive = |row: Option<u32>, col: u32| match row {
+            Some(row) => self.cells.contains(cell_index(width, row, col)) as u16,
+            None => 0,
+        };
+        let west: Vec<Option<u32>> = (0..width)
+            .map(|col| self.boundaries.x.previous(col, width))
+            .collect();
+        let east: Vec<Option<u32>> = (0..width)
+            .map(|col| self.boundaries.x.next(col, width))
+            .collect();
+
+        let mut next_cells = Vec::with_capacity(self.cells.len());
+        let mut columns = vec![0u16; width as usize];
+        for row in 0..height {
+            let north = self.boundaries.y.previous(row, height);
+            let south = self.boundaries.y.next(row, height);
+            for (col, column) in columns.iter_mut().enumerate() {
+                let col = col as u32;
+                *column = alive(north, col) << 2 | alive(Some(row), col) << 1 | alive(south, col);
+            }
+
+            let column_bits = |col: Option<u32>| col.map_or(0, |col| columns[col as usize]);
+            for col in 0..width as usize {
+                let window =
+                    column_bits(west[col]) << 6 | columns[col] << 3 | column_bits(east[col]);
+                next_cells.push(table[window as usize]);
+            }
+        }
+
+        self.changed_cells.clear();
+        for (idx, next) in next_cells.into_iter().enumerate() {
+            let alive = next == 1;
+            if self.cells.contains(idx) != alive {
+                self.changed_cells.push(idx as u32);
+                if alive {
+                    self.live_count += 1;
+                } else {
+                    self.live_count -= 1;
+                }
+            }
+
+            self.buffer_cells.set(idx, alive);
+        }
+
+        std::mem::swap(&mut self.cells, &mut self.buffer_cells);
+
+        self.finish_step();
+    }
+}
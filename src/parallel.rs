@@ -0,0 +1,81 @@
+use std::thread;
+
+use crate::{cell_index, count_live_neighbors, Cell, Universe};
+
+impl Universe {
+    /// Advance the universe by a single generation like `step_once`,
+    /// splitting the rows across one thread per available core.
+    ///
+    /// Every thread only reads `cells`, so the rows can be computed
+    /// independently and are then written into the back buffer in order.
+    /// With a single thread to work with, or on targets such as
+    /// `wasm32-unknown-unknown` that can't tell how many there are, it just
+    /// calls `step_once` instead of spawning any.
+    pub(crate) fn step_parallel(&mut self) {
+        let threads = thread::available_parallelism()
+            .map_or(1, |threads| threads.get())
+            .min(self.height as usize);
+        if threads <= 1 {
+            self.step_once();
+            return;
+        }
+
+        self.push_history();
+
+        let rows_per_thread = self.height.div_ceil(threads as u32).max(1);
+
+        let cells = &self.cells;
+        let (width, height) = (self.width, self.height);
+        let (rule, boundaries, neighborhood) = (self.rule, self.boundaries, self.neighborhood);
+
+        let next_cells: Vec<Vec<Cell>> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..height)
+                .step_by(rows_per_thread as usize)
+                .map(|top| {
+                    let bottom = (top + rows_per_thread).min(height);
+                    scope.spawn(move || {
+                        (top..bottom)
+                            .flat_map(|row| (0..width).map(move |col| (row, col)))
+                            .map(|(row, col)| {
+                                let cell = Cell::from(cells.contains(cell_index(width, row, col)));
+                                let live_neighbors = count_live_neighbors(
+                                    cells,
+                                    width,
+                                    height,
+                                    boundaries,
+                                    neighborhood,
+                                    row,
+                                    col,
+                                );
+                                rule.next_cell(cell, live_neighbors)
+                            })
+                            .collect()
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("tick worker thread panicked"))
+                .collect()
+        });
+
+        self.changed_cells.clear();
+        for (idx, next_cell) in next_cells.into_iter().flatten().enumerate() {
+            let cell = Cell::from(self.cells.contains(idx));
+            if cell != next_cell {
+                self.changed_cells.push(idx as u32);
+                match next_cell {
+                    Cell::Alive => self.live_count += 1,
+                    Cell::Dead => self.live_count -= 1,
+                }
+            }
+
+            self.buffer_cells.set(idx, next_cell == Cell::Alive);
+        }
+
+        std::mem::swap(&mut self.cells, &mut self.buffer_cells);
+
+        self.finish_step();
+    }
+}
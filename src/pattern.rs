@@ -0,0 +1,195 @@
+use std::str::FromStr;
+
+use wasm_bindgen::prelude::*;
+
+/// The built-in patterns, placed relative to an anchor cell.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pattern {
+    /// A south-east bound glider centered on the anchor.
+    Glider,
+    /// A period 3 pulsar centered on the anchor.
+    Pulsar,
+    /// A horizontal blinker centered on the anchor.
+    Blinker,
+    /// A beacon whose upper block has its bottom-right cell on the anchor.
+    Beacon,
+    /// An east-bound lightweight spaceship (LWSS) centered on the anchor.
+    Lwss,
+    /// A Gosper glider gun with its top-left corner on the anchor. It fires
+    /// a south-east bound glider every 30 generations.
+    GosperGliderGun,
+    /// A block with its top-left cell on the anchor.
+    Block,
+}
+
+impl Pattern {
+    /// The `(row, column)` offsets of the live cells from the anchor.
+    pub fn offsets(self) -> &'static [(i32, i32)] {
+        match self {
+            Pattern::Glider => &[(-1, -1), (0, 0), (0, 1), (1, -1), (1, 0)],
+            Pattern::Pulsar => &[
+                (-6, -4),
+                (-6, -3),
+                (-6, -2),
+                (-6, 4),
+                (-6, 3),
+                (-6, 2),
+                (6, -4),
+                (6, -3),
+                (6, -2),
+                (6, 4),
+                (6, 3),
+                (6, 2),
+                (-1, -4),
+                (-1, -3),
+                (-1, -2),
+                (-1, 4),
+                (-1, 3),
+                (-1, 2),
+                (1, -4),
+                (1, -3),
+                (1, -2),
+                (1, 4),
+                (1, 3),
+                (1, 2),
+                (-4, -6),
+                (-3, -6),
+                (-2, -6),
+                (4, -6),
+                (3, -6),
+                (2, -6),
+                (-4, 6),
+                (-3, 6),
+                (-2, 6),
+                (4, 6),
+                (3, 6),
+                (2, 6),
+                (-4, -1),
+                (-3, -1),
+                (-2, -1),
+                (4, -1),
+                (3, -1),
+                (2, -1),
+                (-4, 1),
+                (-3, 1),
+                (-2, 1),
+                (4, 1),
+                (3, 1),
+                (2, 1),
+            ],
+            Pattern::Blinker => &[(0, -1), (0, 0), (0, 1)],
+            Pattern::Beacon => &[
+                (-1, -1),
+                (-1, 0),
+                (0, -1),
+                (0, 0),
+                (1, 1),
+                (1, 2),
+                (2, 1),
+                (2, 2),
+            ],
+            Pattern::Lwss => &[
+                (-1, -2),
+                (-1, 1),
+                (0, 2),
+                (1, -2),
+                (1, 2),
+                (2, -1),
+                (2, 0),
+                (2, 1),
+                (2, 2),
+            ],
+            Pattern::GosperGliderGun => &[
+                (0, 24),
+                (1, 22),
+                (1, 24),
+                (2, 12),
+                (2, 13),
+                (2, 20),
+                (2, 21),
+                (2, 34),
+                (2, 35),
+                (3, 11),
+                (3, 15),
+                (3, 20),
+                (3, 21),
+                (3, 34),
+                (3, 35),
+                (4, 0),
+                (4, 1),
+                (4, 10),
+                (4, 16),
+                (4, 20),
+                (4, 21),
+                (5, 0),
+                (5, 1),
+                (5, 10),
+                (5, 14),
+                (5, 16),
+                (5, 17),
+                (5, 22),
+                (5, 24),
+                (6, 10),
+                (6, 16),
+                (6, 24),
+                (7, 11),
+                (7, 15),
+                (8, 12),
+                (8, 13),
+            ],
+            Pattern::Block => &[(0, 0), (0, 1), (1, 0), (1, 1)],
+        }
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = String;
+
+    /// Look a pattern up by name, such as `glider` or `gun`, ignoring case.
+    fn from_str(name: &str) -> Result<Pattern, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "glider" => Ok(Pattern::Glider),
+            "pulsar" => Ok(Pattern::Pulsar),
+            "blinker" => Ok(Pattern::Blinker),
+            "beacon" => Ok(Pattern::Beacon),
+            "lwss" => Ok(Pattern::Lwss),
+            "gun" | "gosper_glider_gun" => Ok(Pattern::GosperGliderGun),
+            "block" => Ok(Pattern::Block),
+            _ => Err(format!(
+                "unknown pattern '{}', expected one of glider, pulsar, blinker, beacon, \
+                 lwss, gun or block",
+                name
+            )),
+        }
+    }
+}
+
+/// The diagonal a glider travels along.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GliderDirection {
+    NE,
+    NW,
+    SE,
+    SW,
+}
+
+impl GliderDirection {
+    /// The offsets of a glider travelling this way, reflecting the
+    /// south-east bound `Pattern::Glider` across its center.
+    pub fn glider_offsets(self) -> Vec<(i32, i32)> {
+        let (row_sign, col_sign) = match self {
+            GliderDirection::NE => (-1, 1),
+            GliderDirection::NW => (-1, -1),
+            GliderDirection::SE => (1, 1),
+            GliderDirection::SW => (1, -1),
+        };
+
+        Pattern::Glider
+            .offsets()
+            .iter()
+            .map(|&(d_row, d_col)| (d_row * row_sign, d_col * col_sign))
+            .collect()
+    }
+}
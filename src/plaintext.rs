@@ -0,0 +1,43 @@
+/// The size and live cells of a pattern decoded by `decode`.
+pub struct Grid {
+    pub height: u32,
+    pub width: u32,
+    /// The row and column of every live cell, in row-major order.
+    pub cells: Vec<(u32, u32)>,
+}
+
+/// Decode a pattern in the LifeWiki plaintext format, where every line is a
+/// row of `.` (dead) and `O` or `*` (alive) cells.
+///
+/// Lines starting with `!` are comments and skipped. The width is that of
+/// the longest line, and shorter lines are padded with dead cells.
+pub fn decode(text: &str) -> Result<Grid, String> {
+    let mut cells = Vec::new();
+    let mut height = 0;
+    let mut width = 0;
+
+    for line in text.lines().filter(|line| !line.starts_with('!')) {
+        let mut column = 0;
+        for c in line.trim_end().chars() {
+            match c {
+                '.' => {}
+                'O' | '*' => cells.push((height, column)),
+                c => return Err(format!("unexpected '{}' in plaintext pattern", c)),
+            }
+            column += 1;
+        }
+
+        width = width.max(column);
+        height += 1;
+    }
+
+    if height == 0 || width == 0 {
+        return Err("plaintext pattern has no cells".to_string());
+    }
+
+    Ok(Grid {
+        height,
+        width,
+        cells,
+    })
+}
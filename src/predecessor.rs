@@ -0,0 +1,88 @@
+use crate::bitset::FixedBitSet;
+use crate::{Cell, Universe};
+
+/// The largest universe `Universe::find_predecessor` searches, in cells.
+const MAX_PREDECESSOR_CELLS: usize = 36;
+
+/// A depth-first search for a generation that evolves into `target`.
+struct Search<'a> {
+    universe: &'a Universe,
+    target: &'a FixedBitSet,
+    /// The candidate predecessor, filled in cell by cell.
+    cells: FixedBitSet,
+    /// The target cells that can be checked once the candidate is filled in
+    /// up to each index, because their whole neighborhood is known by then.
+    checks: Vec<Vec<usize>>,
+}
+
+impl<'a> Search<'a> {
+    /// Whether the target cell `idx` is what the candidate evolves into.
+    fn matches(&self, idx: usize) -> bool {
+        let width = self.universe.width;
+        let live_neighbors =
+            self.universe
+                .rule_count(&self.cells, idx as u32 / width, idx as u32 % width);
+
+        let next = self
+            .universe
+            .evolve(Cell::from(self.cells.contains(idx)), live_neighbors);
+        next == Cell::from(self.target.contains(idx))
+    }
+
+    /// Try both states for cell `idx` and every cell after it, returning
+    /// whether a predecessor was found.
+    fn fill(&mut self, idx: usize) -> bool {
+        if idx == self.cells.len() {
+            return true;
+        }
+
+        for &alive in &[false, true] {
+            self.cells.set(idx, alive);
+            let consistent = self.checks[idx].iter().all(|&target| self.matches(target));
+            if consistent && self.fill(idx + 1) {
+                return true;
+            }
+        }
+
+        self.cells.set(idx, false);
+        false
+    }
+}
+
+impl Universe {
+    /// Search for cells that evolve into the current generation, or `None`
+    /// if there are none or the universe is too large to search.
+    ///
+    /// Cells are filled in one at a time in row-major order, and every
+    /// target cell is checked as soon as its whole neighborhood is known, so
+    /// most dead ends are abandoned early.
+    pub(crate) fn predecessor(&self) -> Option<FixedBitSet> {
+        let len = self.cells.len();
+        if len > MAX_PREDECESSOR_CELLS || self.range > 1 {
+            return None;
+        }
+
+        let mut checks = vec![Vec::new(); len];
+        for idx in 0..len {
+            let ready = self
+                .neighbor_indices(idx)
+                .iter()
+                .flatten()
+                .fold(idx, |ready, &neighbor| ready.max(neighbor));
+            checks[ready].push(idx);
+        }
+
+        let mut search = Search {
+            universe: self,
+            target: &self.cells,
+            cells: FixedBitSet::with_capacity(len),
+            checks,
+        };
+
+        if search.fill(0) {
+            Some(search.cells)
+        } else {
+            None
+        }
+    }
+}
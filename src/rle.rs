@@ -0,0 +1,154 @@
+/// The `x` and `y` given by an `x = .., y = ..` header line, if it has both.
+fn header_size(line: &str) -> Option<(u32, u32)> {
+    let mut width = None;
+    let mut height = None;
+    for (key, value) in line.split(',').filter_map(|field| field.split_once('=')) {
+        match key.trim() {
+            "x" => width = value.trim().parse().ok(),
+            "y" => height = value.trim().parse().ok(),
+            _ => {}
+        }
+    }
+
+    Some((width?, height?))
+}
+
+/// Decode a pattern in run-length encoded (RLE) format into the row and
+/// column offsets of its live cells, relative to the pattern's top-left
+/// corner.
+///
+/// Comment lines starting with `#` and the `x = .., y = ..` header line are
+/// skipped. The body is made of `b` (dead), `o` (alive) and `$` (end of row)
+/// tags, each optionally preceded by a run count, and is terminated by `!`.
+/// A run that reaches past the header's `x` columns or `y` rows, or past
+/// `max_width` columns or `max_height` rows, is an error.
+pub fn decode(rle: &str, max_height: u32, max_width: u32) -> Result<Vec<(u32, u32)>, String> {
+    let (width, height) = match rle
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with('x'))
+    {
+        Some(header) => {
+            let (width, height) =
+                header_size(header).ok_or_else(|| format!("invalid RLE header '{}'", header))?;
+            (width.min(max_width), height.min(max_height))
+        }
+        None => (max_width, max_height),
+    };
+    let advance = |start: u32, count: u32, limit: u32, what: &str| {
+        start
+            .checked_add(count)
+            .filter(|&end| end <= limit)
+            .ok_or_else(|| format!("RLE pattern runs past its {} {}", limit, what))
+    };
+
+    let mut cells = Vec::new();
+    let mut row = 0;
+    let mut column = 0;
+    let mut run: Option<u32> = None;
+
+    let body = rle
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#') && !line.starts_with('x'));
+
+    for line in body {
+        for c in line.chars() {
+            if let Some(digit) = c.to_digit(10) {
+                run = run
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|run| run.checked_add(digit));
+                if run.is_none() {
+                    return Err("run count in RLE pattern is too large".to_string());
+                }
+                continue;
+            }
+
+            if c.is_whitespace() {
+                continue;
+            }
+
+            let count = run.take().unwrap_or(1);
+            match c {
+                'b' => column = advance(column, count, width, "columns")?,
+                'o' => {
+                    let end = advance(column, count, width, "columns")?;
+                    advance(row, 1, height, "rows")?;
+                    cells.extend((column..end).map(|column| (row, column)));
+                    column = end;
+                }
+                '$' => {
+                    row = advance(row, count, height, "rows")?;
+                    column = 0;
+                }
+                '!' => return Ok(cells),
+                c => return Err(format!("unexpected '{}' in RLE pattern", c)),
+            }
+        }
+    }
+
+    Err("RLE pattern is missing its terminating '!'".to_string())
+}
+
+/// Maximum line length of an encoded pattern, as recommended by the format.
+const LINE_LENGTH: usize = 70;
+
+/// Append a single run to the encoded pattern, wrapping lines so they stay
+/// within `LINE_LENGTH`.
+fn push_run(lines: &mut Vec<String>, count: u32, tag: char) {
+    let run = match count {
+        0 => return,
+        1 => tag.to_string(),
+        count => format!("{}{}", count, tag),
+    };
+
+    match lines.last_mut() {
+        Some(line) if line.len() + run.len() <= LINE_LENGTH => line.push_str(&run),
+        _ => lines.push(run),
+    }
+}
+
+/// Encode live cells, given as row and column pairs in row-major order, as
+/// an RLE pattern trimmed to their bounding box.
+pub fn encode(cells: &[(u32, u32)], rule: &str) -> String {
+    let min_row = cells.first().map_or(0, |cell| cell.0);
+    let max_row = cells.last().map_or(0, |cell| cell.0);
+    let min_column = cells.iter().map(|cell| cell.1).min().unwrap_or(0);
+    let max_column = cells.iter().map(|cell| cell.1).max().unwrap_or(0);
+
+    let (width, height) = if cells.is_empty() {
+        (0, 0)
+    } else {
+        (max_column - min_column + 1, max_row - min_row + 1)
+    };
+
+    let mut lines = vec![format!("x = {}, y = {}, rule = {}", width, height, rule)];
+    lines.push(String::new());
+
+    let mut row = min_row;
+    let mut column = min_column;
+    let mut alive = 0;
+
+    for &(cell_row, cell_column) in cells {
+        if cell_row > row || cell_column > column {
+            push_run(&mut lines, alive, 'o');
+            alive = 0;
+        }
+
+        if cell_row > row {
+            push_run(&mut lines, cell_row - row, '$');
+            row = cell_row;
+            column = min_column;
+        }
+
+        push_run(&mut lines, cell_column - column, 'b');
+        alive += 1;
+        column = cell_column + 1;
+    }
+
+    push_run(&mut lines, alive, 'o');
+    push_run(&mut lines, 1, '!');
+
+    lines.join("\n")
+}
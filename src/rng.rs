@@ -0,0 +1,63 @@
+use std::cell::RefCell;
+
+/// The seed of the shared generator until `seed_default` is called, drawn
+/// from `Math.random` so each page load starts from a different one.
+fn initial_seed() -> u64 {
+    (js_sys::Math::random() * (1u64 << 53) as f64) as u64
+}
+
+thread_local! {
+    /// The generator behind randomness that isn't given its own seed, such
+    /// as `Universe::new_random`.
+    static DEFAULT: RefCell<Rng> = RefCell::new(Rng::new(initial_seed()));
+}
+
+/// Restart the shared generator from `seed`.
+pub fn seed_default(seed: u64) {
+    DEFAULT.with(|rng| *rng.borrow_mut() = Rng::new(seed));
+}
+
+/// Run `f` with the shared generator.
+pub fn with_default<T>(f: impl FnOnce(&mut Rng) -> T) -> T {
+    DEFAULT.with(|rng| f(&mut rng.borrow_mut()))
+}
+
+/// A small, deterministic pseudo-random number generator (xorshift64*), so
+/// simulations can be reproduced from a seed.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        // Scramble the seed with SplitMix64 so that small or similar seeds
+        // still start from well mixed, non-zero states.
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        Rng {
+            state: if z == 0 { 0x9E37_79B9_7F4A_7C15 } else { z },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// A uniformly distributed float in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A uniformly distributed integer in `[0, bound)`, for a non-zero
+    /// `bound`.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+}
@@ -0,0 +1,108 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Cell;
+
+/// A Life-like rule in B/S notation, e.g. `B3/S23` for Conway's Game of Life.
+///
+/// Bit `n` of `birth` is set when a dead cell with `n` live neighbors comes
+/// alive, and bit `n` of `survival` when a live cell with `n` live neighbors
+/// stays alive. Every other cell is dead in the next generation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule {
+    birth: u16,
+    survival: u16,
+}
+
+impl Rule {
+    /// Conway's Game of Life, `B3/S23`.
+    pub fn conway() -> Rule {
+        Rule {
+            birth: 1 << 3,
+            survival: 1 << 2 | 1 << 3,
+        }
+    }
+
+    /// The state of a cell in the next generation.
+    pub fn next_cell(&self, cell: Cell, live_neighbors: u8) -> Cell {
+        let mask = match cell {
+            Cell::Alive => self.survival,
+            Cell::Dead => self.birth,
+        };
+
+        if mask & (1 << live_neighbors) != 0 {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Rule {
+        Rule::conway()
+    }
+}
+
+/// Parse the neighbor counts following a `B` or `S` prefix into a bitmask.
+fn parse_counts(counts: &str) -> Result<u16, String> {
+    let mut mask = 0;
+
+    for c in counts.chars() {
+        match c.to_digit(10) {
+            Some(n) if n <= 8 => mask |= 1 << n,
+            _ => return Err(format!("invalid neighbor count '{}' in rule", c)),
+        }
+    }
+
+    Ok(mask)
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(rule: &str) -> Result<Rule, String> {
+        let mut birth = None;
+        let mut survival = None;
+
+        for part in rule.trim().split('/') {
+            let mut chars = part.chars();
+            let target = match chars.next() {
+                Some('B') | Some('b') => &mut birth,
+                Some('S') | Some('s') => &mut survival,
+                _ => return Err(format!("expected a B or S section in rule '{}'", rule)),
+            };
+
+            if target.is_some() {
+                return Err(format!("duplicate section '{}' in rule '{}'", part, rule));
+            }
+
+            *target = Some(parse_counts(chars.as_str())?);
+        }
+
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(Rule { birth, survival }),
+            _ => Err(format!("rule '{}' needs both a B and an S section", rule)),
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "B")?;
+        for n in 0..=8 {
+            if self.birth & (1 << n) != 0 {
+                write!(f, "{}", n)?;
+            }
+        }
+
+        write!(f, "/S")?;
+        for n in 0..=8 {
+            if self.survival & (1 << n) != 0 {
+                write!(f, "{}", n)?;
+            }
+        }
+
+        Ok(())
+    }
+}
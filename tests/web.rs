@@ -3,27 +3,34 @@
 #![cfg(target_arch = "wasm32")]
 
 extern crate wasm_game_of_life;
-use wasm_game_of_life::Universe;
+use wasm_game_of_life::{
+    seed_rng, set_max_cells, BoundaryMode, Cell, GliderDirection, Info, Neighborhood, PasteMode,
+    Pattern, SeedPattern, Universe,
+};
 
 extern crate wasm_bindgen_test;
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_test::*;
 
 wasm_bindgen_test_configure!(run_in_browser);
 
 #[cfg(test)]
 pub fn input_spaceship() -> Universe {
-    let mut universe = Universe::new();
-    universe.set_width(6);
-    universe.set_height(6);
+    let mut universe = Universe::new(6, 6).unwrap();
+    universe.set_width(6).unwrap();
+    universe.set_height(6).unwrap();
     universe.set_cells(&[(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)]);
     universe
 }
 
 #[cfg(test)]
 pub fn expected_spaceship() -> Universe {
-    let mut universe = Universe::new();
-    universe.set_width(6);
-    universe.set_height(6);
+    let mut universe = Universe::new(6, 6).unwrap();
+    universe.set_width(6).unwrap();
+    universe.set_height(6).unwrap();
     universe.set_cells(&[(2, 1), (2, 3), (3, 2), (3, 3), (4, 2)]);
     universe
 }
@@ -43,6 +50,2729 @@ pub fn test_tick() {
     assert_eq!(&input_universe.get_cells(), &expected_universe.get_cells());
 }
 
+#[cfg(test)]
+pub fn live_cells_in_columns(universe: &Universe, columns: std::ops::Range<u32>) -> usize {
+    universe
+        .get_cells()
+        .iter()
+        .enumerate()
+        .filter(|&(idx, &cell)| {
+            cell == Cell::Alive && columns.contains(&(idx as u32 % universe.width()))
+        })
+        .count()
+}
+
+#[wasm_bindgen_test]
+pub fn test_dead_boundary_stops_glider_at_wall() {
+    // A tall, narrow universe so the south-east bound glider reaches the
+    // right edge long before the bottom one.
+    let mut universe = Universe::new_dead(20, 8).unwrap();
+    universe.set_boundary_mode(BoundaryMode::Dead);
+    universe.insert_glider(2, 2, GliderDirection::SE);
+
+    for _ in 0..40 {
+        universe.tick();
+    }
+
+    // The glider crashes into the wall and settles into a block instead of
+    // reappearing on the left.
+    let mut expected_universe = Universe::new_dead(20, 8).unwrap();
+    expected_universe.set_cells(&[(7, 6), (7, 7), (8, 6), (8, 7)]);
+
+    assert_eq!(live_cells_in_columns(&universe, 0..2), 0);
+    assert_eq!(&universe.get_cells(), &expected_universe.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_toroidal_boundary_wraps_glider() {
+    let mut universe = Universe::new_dead(20, 8).unwrap();
+    assert_eq!(universe.boundary_mode(), BoundaryMode::Toroidal);
+    universe.insert_glider(2, 2, GliderDirection::SE);
+
+    for _ in 0..24 {
+        universe.tick();
+    }
+
+    assert!(live_cells_in_columns(&universe, 0..2) > 0);
+}
+
+#[cfg(test)]
+pub fn insert_replicator(universe: &mut Universe, row: u32, column: u32) {
+    let cells: Vec<(u32, u32)> = [
+        (0, 2),
+        (0, 3),
+        (0, 4),
+        (1, 1),
+        (1, 4),
+        (2, 0),
+        (2, 4),
+        (3, 0),
+        (3, 3),
+        (4, 0),
+        (4, 1),
+        (4, 2),
+    ]
+    .iter()
+    .map(|&(r, c)| (row + r, column + c))
+    .collect();
+    universe.set_cells(&cells);
+}
+
+#[wasm_bindgen_test]
+pub fn test_highlife_replicator() {
+    let mut universe = Universe::new_dead(24, 24).unwrap();
+    universe.set_rule("B36/S23").unwrap();
+    assert_eq!(universe.rule(), "B36/S23");
+    insert_replicator(&mut universe, 10, 10);
+
+    for _ in 0..12 {
+        universe.tick();
+    }
+
+    // After 12 generations the replicator has made two diagonal copies of
+    // itself.
+    let mut expected_universe = Universe::new_dead(24, 24).unwrap();
+    insert_replicator(&mut expected_universe, 8, 8);
+    insert_replicator(&mut expected_universe, 12, 12);
+
+    assert_eq!(&universe.get_cells(), &expected_universe.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_default_rule_is_conway() {
+    let mut universe = Universe::new_dead(6, 6).unwrap();
+    assert_eq!(universe.rule(), "B3/S23");
+
+    universe.set_rule("b3/s23").unwrap();
+    assert_eq!(universe.rule(), "B3/S23");
+
+    // Conway's rules still drive the spaceship from `test_tick`.
+    let mut input_universe = input_spaceship();
+    input_universe.tick();
+    assert_eq!(
+        &input_universe.get_cells(),
+        &expected_spaceship().get_cells()
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_invalid_rule_is_rejected() {
+    let mut universe = Universe::new_dead(6, 6).unwrap();
+
+    assert!(universe.set_rule("B3").is_err());
+    assert!(universe.set_rule("B39/S23").is_err());
+    assert!(universe.set_rule("X3/S23").is_err());
+    assert_eq!(universe.rule(), "B3/S23");
+}
+
+#[wasm_bindgen_test]
+pub fn test_generation_counts_ticks() {
+    let mut universe = Universe::new(16, 16).unwrap();
+    assert_eq!(universe.generation(), 0);
+
+    for _ in 0..10 {
+        universe.tick();
+    }
+
+    assert_eq!(universe.generation(), 10);
+
+    universe.set_width(8).unwrap();
+    assert_eq!(universe.generation(), 0);
+
+    assert!(universe.set_width(0).is_err());
+    assert!(universe.set_height(u32::MAX).is_err());
+    assert_eq!(universe.width(), 8);
+}
+
+#[wasm_bindgen_test]
+pub fn test_live_count_matches_cells() {
+    let mut universe = Universe::new_random(32, 32).unwrap();
+
+    for _ in 0..5 {
+        universe.tick();
+
+        let expected = universe
+            .get_cells()
+            .iter()
+            .filter(|&&cell| cell == Cell::Alive)
+            .count() as u32;
+        assert_eq!(universe.live_count(), expected);
+    }
+
+    universe.toggle_cell(0, 0).unwrap();
+    universe.insert_glider(10, 10, GliderDirection::SE);
+    let expected = universe
+        .get_cells()
+        .iter()
+        .filter(|&&cell| cell == Cell::Alive)
+        .count() as u32;
+    assert_eq!(universe.live_count(), expected);
+}
+
+/// A straightforward byte-per-cell toroidal Conway tick to check the packed
+/// implementation against.
+#[cfg(test)]
+pub fn reference_tick(height: u32, width: u32, cells: &[Cell]) -> Vec<Cell> {
+    let mut next = cells.to_vec();
+
+    for row in 0..height {
+        for col in 0..width {
+            let mut live_neighbors = 0;
+            for delta_row in [height - 1, 0, 1] {
+                for delta_col in [width - 1, 0, 1] {
+                    if delta_row == 0 && delta_col == 0 {
+                        continue;
+                    }
+
+                    let neighbor_row = (row + delta_row) % height;
+                    let neighbor_col = (col + delta_col) % width;
+                    let idx = (neighbor_row * width + neighbor_col) as usize;
+                    live_neighbors += cells[idx] as u8;
+                }
+            }
+
+            let idx = (row * width + col) as usize;
+            next[idx] = match (cells[idx], live_neighbors) {
+                (Cell::Alive, 2) | (_, 3) => Cell::Alive,
+                _ => Cell::Dead,
+            };
+        }
+    }
+
+    next
+}
+
+#[wasm_bindgen_test]
+pub fn test_packed_tick_matches_reference() {
+    let mut universe = Universe::new(64, 48).unwrap();
+    let mut expected = universe.get_cells();
+
+    for _ in 0..20 {
+        universe.tick();
+        expected = reference_tick(64, 48, &expected);
+
+        assert_eq!(universe.get_cells(), expected);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_matches_reference_on_random_board() {
+    let mut universe = Universe::new_dead(50, 70).unwrap();
+    universe.randomize(0.4, 7);
+    let mut expected = universe.get_cells();
+
+    for _ in 0..50 {
+        universe.tick();
+        expected = reference_tick(50, 70, &expected);
+
+        assert_eq!(universe.get_cells(), expected);
+    }
+
+    // Edits between ticks are picked up by the next generation.
+    universe.toggle_cell(10, 10).unwrap();
+    universe.set_cells(&[(20, 20), (20, 21), (20, 22)]);
+    expected = reference_tick(50, 70, &universe.get_cells());
+    universe.tick();
+    assert_eq!(universe.get_cells(), expected);
+}
+
+#[wasm_bindgen_test]
+pub fn test_cell_views_match_packed_cells() {
+    let mut universe = Universe::new(10, 7).unwrap();
+    universe.tick();
+
+    let cells = universe.get_cells();
+    let len = cells.len();
+
+    let bytes = unsafe { std::slice::from_raw_parts(universe.cells(), len) };
+    assert_eq!(bytes, &cells[..]);
+
+    let bits = unsafe { std::slice::from_raw_parts(universe.cells_bits(), len.div_ceil(32)) };
+    for (idx, &cell) in cells.iter().enumerate() {
+        let alive = bits[idx / 32] & (1 << (idx % 32)) != 0;
+        assert_eq!(alive, cell == Cell::Alive);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_rle_glider() {
+    let mut universe = Universe::new_dead(12, 12).unwrap();
+    universe
+        .insert_rle(
+            4,
+            6,
+            "#N Glider\n#C The phase placed by insert_glider.\nx = 3, y = 3, rule = B3/S23\no$b2o$2o!",
+        )
+        .unwrap();
+
+    let mut expected_universe = Universe::new_dead(12, 12).unwrap();
+    expected_universe.insert_glider(5, 7, GliderDirection::SE);
+
+    assert_eq!(&universe.get_cells(), &expected_universe.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_rle_wraps_and_runs() {
+    let mut universe = Universe::new_dead(6, 6).unwrap();
+    universe.insert_rle(5, 4, "3o2$\n2bo!").unwrap();
+
+    let mut expected_universe = Universe::new_dead(6, 6).unwrap();
+    expected_universe.set_cells(&[(5, 4), (5, 5), (5, 0), (1, 0)]);
+
+    assert_eq!(&universe.get_cells(), &expected_universe.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_malformed_rle_is_rejected() {
+    let mut universe = Universe::new_dead(6, 6).unwrap();
+
+    assert!(universe.insert_rle(0, 0, "bo$2bo$3o").is_err());
+    assert!(universe.insert_rle(0, 0, "bo$2bx$3o!").is_err());
+
+    // Runs must stay within the header's size and the universe.
+    assert!(universe.insert_rle(0, 0, "x = 2, y = 2\n3o!").is_err());
+    assert!(universe.insert_rle(0, 0, "x = 3, y = 2\n3o2$o!").is_err());
+    assert!(universe.insert_rle(0, 0, "999999999o!").is_err());
+    assert!(universe
+        .insert_rle(0, 0, "4294967295b4294967295o!")
+        .is_err());
+    assert!(universe.insert_rle(0, 0, "4294967295$o!").is_err());
+    assert_eq!(universe.live_count(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_to_rle_glider() {
+    let mut universe = Universe::new_dead(12, 12).unwrap();
+    universe.insert_glider(5, 7, GliderDirection::SE);
+
+    assert_eq!(universe.to_rle(), "x = 3, y = 3, rule = B3/S23\no$b2o$2o!");
+    assert_eq!(
+        Universe::new_dead(4, 4).unwrap().to_rle(),
+        "x = 0, y = 0, rule = B3/S23\n!"
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_rle_round_trip() {
+    let mut universe = Universe::new(40, 40).unwrap();
+    for _ in 0..3 {
+        universe.tick();
+    }
+
+    let cells = universe.get_cells();
+    let live = |idx: &usize| cells[*idx] == Cell::Alive;
+    let top = (0..cells.len()).find(live).unwrap() as u32 / 40;
+    let left = (0..cells.len())
+        .filter(live)
+        .map(|idx| idx as u32 % 40)
+        .min()
+        .unwrap();
+
+    let mut round_tripped = Universe::new_dead(40, 40).unwrap();
+    round_tripped
+        .insert_rle(top, left, &universe.to_rle())
+        .unwrap();
+
+    assert_eq!(&round_tripped.get_cells(), &universe.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_glider_at_origin_wraps() {
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.insert_glider(0, 0, GliderDirection::SE);
+
+    let mut expected_universe = Universe::new_dead(8, 8).unwrap();
+    expected_universe.set_cells(&[(7, 7), (0, 0), (0, 1), (1, 7), (1, 0)]);
+
+    assert_eq!(&universe.get_cells(), &expected_universe.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_pulsar_at_origin_wraps() {
+    let mut universe = Universe::new_dead(20, 20).unwrap();
+    universe.insert_pulsar(0, 0);
+
+    // The same pulsar placed in the middle of the universe, shifted back so
+    // its center lands on the origin.
+    let mut centered = Universe::new_dead(20, 20).unwrap();
+    centered.insert_pulsar(10, 10);
+    let centered_cells = centered.get_cells();
+
+    let cells = universe.get_cells();
+    for row in 0..20 {
+        for col in 0..20 {
+            let shifted = ((row + 10) % 20 * 20 + (col + 10) % 20) as usize;
+            assert_eq!(cells[row * 20 + col], centered_cells[shifted]);
+        }
+    }
+
+    assert_eq!(universe.live_count(), 48);
+    assert_eq!(cells[(20 - 6) * 20 + (20 - 4)], Cell::Alive);
+    assert_eq!(cells[4 * 20 + 1], Cell::Alive);
+}
+
+#[wasm_bindgen_test]
+pub fn test_serialize_round_trip() {
+    let mut universe = Universe::new_random(17, 23).unwrap();
+    universe.tick();
+    universe.tick();
+
+    let restored = Universe::deserialize(&universe.serialize()).unwrap();
+
+    assert_eq!(restored.width(), 23);
+    assert_eq!(restored.height(), 17);
+    assert_eq!(restored.generation(), 2);
+    assert_eq!(restored.live_count(), universe.live_count());
+    assert_eq!(&restored.get_cells(), &universe.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_corrupt_serialized_universe_is_rejected() {
+    let bytes = Universe::new(8, 8).unwrap().serialize();
+
+    // Truncated cells.
+    assert!(Universe::deserialize(&bytes[..bytes.len() - 1]).is_err());
+    // Truncated header.
+    assert!(Universe::deserialize(&bytes[..6]).is_err());
+
+    let mut wrong_version = bytes.clone();
+    wrong_version[4] += 1;
+    assert!(Universe::deserialize(&wrong_version).is_err());
+
+    let mut wrong_magic = bytes;
+    wrong_magic[0] = b'X';
+    assert!(Universe::deserialize(&wrong_magic).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_resize_keeps_cells() {
+    let mut universe = Universe::new_dead(5, 5).unwrap();
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+
+    universe.resize(8, 10).unwrap();
+    assert_eq!(universe.height(), 8);
+    assert_eq!(universe.width(), 10);
+
+    let mut expected_universe = Universe::new_dead(8, 10).unwrap();
+    expected_universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    assert_eq!(&universe.get_cells(), &expected_universe.get_cells());
+
+    // The blinker keeps oscillating in place in the larger universe.
+    universe.tick();
+    let mut expected_universe = Universe::new_dead(8, 10).unwrap();
+    expected_universe.set_cells(&[(1, 2), (2, 2), (3, 2)]);
+    assert_eq!(&universe.get_cells(), &expected_universe.get_cells());
+
+    // Shrinking clips the cells outside the new bounds.
+    universe.resize(3, 4).unwrap();
+    let mut expected_universe = Universe::new_dead(3, 4).unwrap();
+    expected_universe.set_cells(&[(1, 2), (2, 2)]);
+    assert_eq!(&universe.get_cells(), &expected_universe.get_cells());
+    assert_eq!(universe.live_count(), 2);
+}
+
+#[wasm_bindgen_test]
+pub fn test_step_back_restores_history() {
+    let mut universe = Universe::new(16, 16).unwrap();
+    universe.set_history_capacity(2);
+    assert!(!universe.step_back());
+
+    let mut remembered = vec![universe.get_cells()];
+    for _ in 0..3 {
+        universe.tick();
+        remembered.push(universe.get_cells());
+    }
+
+    // Only the last two generations fit in the history.
+    assert!(universe.step_back());
+    assert_eq!(universe.generation(), 2);
+    assert_eq!(universe.get_cells(), remembered[2]);
+
+    assert!(universe.step_back());
+    assert_eq!(universe.generation(), 1);
+    assert_eq!(universe.get_cells(), remembered[1]);
+
+    assert!(!universe.step_back());
+    assert_eq!(universe.generation(), 1);
+
+    // Ticking again from a restored state gives the same future.
+    universe.tick();
+    assert_eq!(universe.get_cells(), remembered[2]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_single_cell_queries() {
+    let mut universe = Universe::new_dead(4, 6).unwrap();
+    universe.set_cells(&[(3, 5), (1, 2)]);
+
+    assert_eq!(universe.get_cell(3, 5).unwrap(), Cell::Alive);
+    assert_eq!(universe.get_cell(0, 0).unwrap(), Cell::Dead);
+    assert!(universe.is_alive(1, 2).unwrap());
+    assert!(!universe.is_alive(2, 1).unwrap());
+}
+
+#[wasm_bindgen_test]
+pub fn test_out_of_range_cell_query_is_rejected() {
+    let universe = Universe::new_dead(4, 6).unwrap();
+
+    assert!(universe.get_cell(4, 0).is_err());
+    assert!(universe.get_cell(0, 6).is_err());
+    assert!(universe.is_alive(10, 10).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_clear_kills_all_cells() {
+    let mut universe = Universe::new(12, 9).unwrap();
+    universe.tick();
+    assert!(universe.live_count() > 0);
+
+    universe.clear();
+
+    assert_eq!(universe.live_count(), 0);
+    assert_eq!(universe.height(), 12);
+    assert_eq!(universe.width(), 9);
+    assert_eq!(universe.generation(), 1);
+    assert_eq!(
+        &universe.get_cells(),
+        &Universe::new_dead(12, 9).unwrap().get_cells()
+    );
+
+    // Nothing comes back to life from the old generation.
+    universe.tick();
+    assert_eq!(universe.live_count(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_randomize_is_reproducible() {
+    let mut universe = Universe::new_dead(32, 32).unwrap();
+    universe.randomize(0.3, 42);
+    let first = universe.get_cells();
+    let live_count = universe.live_count();
+
+    universe.tick();
+    universe.randomize(0.3, 42);
+    assert_eq!(universe.get_cells(), first);
+    assert_eq!(universe.live_count(), live_count);
+
+    // Roughly the requested density.
+    assert!(live_count > 32 * 32 / 5 && live_count < 32 * 32 * 2 / 5);
+
+    universe.randomize(0.3, 43);
+    assert_ne!(universe.get_cells(), first);
+}
+
+#[wasm_bindgen_test]
+pub fn test_set_region() {
+    let mut universe = Universe::new_dead(6, 8).unwrap();
+    universe.set_region(1, 2, 3, 4, Cell::Alive);
+    assert_eq!(universe.live_count(), 12);
+
+    let cells = universe.get_cells();
+    for row in 0..6 {
+        for col in 0..8 {
+            let inside = (1..=3).contains(&row) && (2..=5).contains(&col);
+            assert_eq!(cells[row * 8 + col] == Cell::Alive, inside);
+        }
+    }
+
+    universe.set_region(2, 3, 1, 2, Cell::Dead);
+    assert_eq!(universe.live_count(), 10);
+    assert!(!universe.is_alive(2, 3).unwrap());
+    assert!(!universe.is_alive(2, 4).unwrap());
+    assert!(universe.is_alive(2, 5).unwrap());
+
+    // Regions running past the edges are clipped rather than wrapped.
+    universe.set_region(4, 6, 10, 10, Cell::Alive);
+    assert_eq!(universe.live_count(), 14);
+    assert!(!universe.is_alive(0, 0).unwrap());
+
+    universe.set_region(0, 0, 6, 8, Cell::Dead);
+    assert_eq!(universe.live_count(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_changed_cells_of_blinker() {
+    let mut universe = Universe::new_dead(5, 5).unwrap();
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    assert!(universe.changed_cells().is_empty());
+
+    // The ends of the blinker flip each generation, the center stays alive.
+    for _ in 0..3 {
+        universe.tick();
+        assert_eq!(universe.changed_cells(), vec![7, 11, 13, 17]);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_n_matches_repeated_ticks() {
+    let mut universe = Universe::new(24, 24).unwrap();
+    let mut expected_universe = Universe::new(24, 24).unwrap();
+
+    universe.tick_n(5);
+    for _ in 0..5 {
+        expected_universe.tick();
+    }
+
+    assert_eq!(universe.generation(), 5);
+    assert_eq!(universe.live_count(), expected_universe.live_count());
+    assert_eq!(&universe.get_cells(), &expected_universe.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_render_with_custom_symbols() {
+    let mut universe = Universe::new_dead(3, 4).unwrap();
+    universe.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+
+    assert_eq!(universe.render_with('.', '*'), ".*..\n..*.\n***.\n");
+    assert_eq!(universe.render(), "◻◼◻◻\n◻◻◼◻\n◼◼◼◻\n");
+}
+
+#[cfg(test)]
+pub const GOSPER_GLIDER_GUN_RLE: &str = "x = 36, y = 9, rule = B3/S23
+24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$
+10bo5bo7bo$11bo3bo$12b2o!";
+
+#[wasm_bindgen_test]
+pub fn test_incremental_tick_matches_tick() {
+    for &boundary_mode in &[
+        BoundaryMode::Toroidal,
+        BoundaryMode::Dead,
+        BoundaryMode::Mirror,
+    ] {
+        let mut universe = Universe::new_dead(48, 64).unwrap();
+        universe.set_boundary_mode(boundary_mode);
+        universe.insert_rle(2, 2, GOSPER_GLIDER_GUN_RLE).unwrap();
+        universe.set_region(30, 40, 10, 10, Cell::Alive);
+
+        let mut expected_universe = Universe::new_dead(48, 64).unwrap();
+        expected_universe.set_boundary_mode(boundary_mode);
+        expected_universe
+            .insert_rle(2, 2, GOSPER_GLIDER_GUN_RLE)
+            .unwrap();
+        expected_universe.set_region(30, 40, 10, 10, Cell::Alive);
+
+        for generation in 0..150 {
+            // Edits in between generations are picked up too.
+            if generation == 75 {
+                universe.insert_glider(20, 20, GliderDirection::SE);
+                expected_universe.insert_glider(20, 20, GliderDirection::SE);
+            }
+
+            universe.tick_incremental();
+            expected_universe.tick();
+
+            assert_eq!(universe.get_cells(), expected_universe.get_cells());
+            assert_eq!(universe.changed_cells(), expected_universe.changed_cells());
+            assert_eq!(universe.live_count(), expected_universe.live_count());
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_blinker_and_beacon() {
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.insert_blinker(2, 2);
+    assert_eq!(universe.live_count(), 3);
+
+    universe.tick();
+    let mut expected_universe = Universe::new_dead(8, 8).unwrap();
+    expected_universe.set_cells(&[(1, 2), (2, 2), (3, 2)]);
+    assert_eq!(&universe.get_cells(), &expected_universe.get_cells());
+
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.insert_beacon(2, 2);
+    assert_eq!(universe.live_count(), 8);
+
+    // The inner corners die and come back every other generation.
+    universe.tick();
+    assert_eq!(universe.live_count(), 6);
+    universe.tick();
+    assert_eq!(universe.live_count(), 8);
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_lwss_travels_east() {
+    let mut universe = Universe::new_dead(8, 12).unwrap();
+    universe.insert_lwss(3, 4);
+    assert_eq!(universe.live_count(), 9);
+
+    universe.tick_n(4);
+
+    let mut expected_universe = Universe::new_dead(8, 12).unwrap();
+    expected_universe.insert_lwss(3, 6);
+    assert_eq!(&universe.get_cells(), &expected_universe.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_gosper_glider_gun_fires_gliders() {
+    let mut universe = Universe::new_dead(40, 50).unwrap();
+    universe.set_boundary_mode(BoundaryMode::Dead);
+    universe.insert_gosper_glider_gun(1, 1);
+    assert_eq!(universe.live_count(), 36);
+
+    // Every 30 generations the gun returns to the same phase, leaving one
+    // more five-cell glider behind.
+    universe.tick_n(30);
+    let after_one_period = universe.live_count();
+    universe.tick_n(30);
+    assert_eq!(universe.live_count(), after_one_period + 5);
+}
+
+#[wasm_bindgen_test]
+pub fn test_live_bounds() {
+    let mut universe = Universe::new_dead(10, 12).unwrap();
+    assert_eq!(universe.live_bounds(), None);
+    assert_eq!(universe.live_bounds_js(), None);
+
+    universe.insert_blinker(6, 3);
+    assert_eq!(universe.live_bounds(), Some((6, 2, 6, 4)));
+
+    universe.tick();
+    assert_eq!(universe.live_bounds(), Some((5, 3, 7, 3)));
+    assert_eq!(universe.live_bounds_js(), Some(vec![5, 3, 7, 3]));
+}
+
+#[wasm_bindgen_test]
+pub fn test_is_stable() {
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3)]);
+    assert!(!universe.is_stable());
+
+    universe.tick();
+    assert!(universe.is_stable());
+
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.insert_blinker(3, 3);
+    for _ in 0..10 {
+        universe.tick();
+        assert!(!universe.is_stable());
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_on_stable_callback_fires_once() {
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    // A beehive predecessor that settles down after a few generations.
+    universe.set_cells(&[(3, 2), (3, 3), (3, 4), (4, 3)]);
+
+    let calls = Rc::new(RefCell::new(0));
+    let counter = calls.clone();
+    let callback = Closure::wrap(Box::new(move || {
+        *counter.borrow_mut() += 1;
+    }) as Box<dyn FnMut()>);
+    universe.set_on_stable(
+        callback
+            .as_ref()
+            .unchecked_ref::<js_sys::Function>()
+            .clone(),
+    );
+
+    for _ in 0..20 {
+        universe.tick();
+    }
+
+    assert!(universe.is_stable());
+    assert_eq!(*calls.borrow(), 1);
+}
+
+#[wasm_bindgen_test]
+pub fn test_detect_period() {
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.insert_blinker(3, 3);
+    let before = universe.get_cells();
+    assert_eq!(universe.detect_period(10), Some(2));
+    assert_eq!(universe.get_cells(), before);
+    assert_eq!(universe.generation(), 0);
+
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3)]);
+    assert_eq!(universe.detect_period(10), Some(1));
+
+    let mut universe = Universe::new_dead(20, 20).unwrap();
+    universe.insert_pulsar(10, 10);
+    assert_eq!(universe.detect_period(10), Some(3));
+    assert_eq!(universe.detect_period(2), None);
+
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.insert_glider(1, 1, GliderDirection::SE);
+    assert_eq!(universe.detect_period(3), None);
+}
+
+#[wasm_bindgen_test]
+pub fn test_neighbor_count_wraps_at_corners() {
+    let mut universe = Universe::new_dead(4, 5).unwrap();
+    universe.set_cells(&[(0, 0), (0, 4), (3, 0), (3, 4), (1, 1)]);
+
+    // Every corner touches the other three corners across the edges, plus
+    // (1, 1) for the top-left one.
+    assert_eq!(universe.neighbor_count(0, 0).unwrap(), 4);
+    assert_eq!(universe.neighbor_count(0, 4).unwrap(), 3);
+    assert_eq!(universe.neighbor_count(3, 0).unwrap(), 3);
+    assert_eq!(universe.neighbor_count(3, 4).unwrap(), 3);
+    assert_eq!(universe.neighbor_count(2, 2).unwrap(), 1);
+
+    universe.set_boundary_mode(BoundaryMode::Dead);
+    assert_eq!(universe.neighbor_count(0, 0).unwrap(), 1);
+    assert_eq!(universe.neighbor_count(3, 4).unwrap(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_out_of_range_neighbor_count_is_rejected() {
+    let universe = Universe::new_dead(4, 5).unwrap();
+
+    assert!(universe.neighbor_count(4, 0).is_err());
+    assert!(universe.neighbor_count(0, 5).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_parallel_matches_tick() {
+    for &boundary_mode in &[
+        BoundaryMode::Toroidal,
+        BoundaryMode::Dead,
+        BoundaryMode::Mirror,
+    ] {
+        let mut sequential = Universe::new(67, 45).unwrap();
+        sequential.set_boundary_mode(boundary_mode);
+        let mut parallel = Universe::new(67, 45).unwrap();
+        parallel.set_boundary_mode(boundary_mode);
+
+        for _ in 0..20 {
+            sequential.tick();
+            parallel.tick_parallel();
+
+            assert_eq!(parallel.get_cells(), sequential.get_cells());
+            assert_eq!(parallel.changed_cells(), sequential.changed_cells());
+            assert_eq!(parallel.live_count(), sequential.live_count());
+        }
+        assert_eq!(parallel.generation(), 20);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_live_cells() {
+    let mut universe = Universe::new_dead(10, 12).unwrap();
+    assert!(universe.live_cells().is_empty());
+
+    universe.insert_blinker(6, 3);
+    assert_eq!(
+        universe.live_cells(),
+        vec![6 * 12 + 2, 6 * 12 + 3, 6 * 12 + 4]
+    );
+
+    universe.tick();
+    assert_eq!(
+        universe.live_cells(),
+        vec![5 * 12 + 3, 6 * 12 + 3, 7 * 12 + 3]
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_clipped_drops_cells_off_the_edge() {
+    let mut wrapped = Universe::new_dead(10, 10).unwrap();
+    wrapped.insert_glider(0, 5, GliderDirection::SE);
+    assert_eq!(wrapped.live_count(), 5);
+    assert_eq!(wrapped.get_cell(9, 4).unwrap(), Cell::Alive);
+
+    let mut clipped = Universe::new_dead(10, 10).unwrap();
+    clipped.insert_clipped(Pattern::Glider, 0, 5);
+    assert_eq!(clipped.live_count(), 4);
+    assert_eq!(clipped.get_cell(9, 4).unwrap(), Cell::Dead);
+    assert_eq!(
+        clipped.live_cells(),
+        vec![5, 6, 10 + 4, 10 + 5],
+        "the cells inside the universe are still inserted"
+    );
+
+    // Away from the edges both give the same result.
+    let mut wrapped = Universe::new_dead(40, 40).unwrap();
+    wrapped.insert_gosper_glider_gun(2, 2);
+    let mut clipped = Universe::new_dead(40, 40).unwrap();
+    clipped.insert_clipped(Pattern::GosperGliderGun, 2, 2);
+    assert_eq!(clipped.get_cells(), wrapped.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_new_pattern() {
+    let universe = Universe::new_pattern(4, 4, SeedPattern::Checkerboard).unwrap();
+    assert_eq!(universe.live_count(), 8);
+    assert_eq!(universe.get_cell(0, 0).unwrap(), Cell::Alive);
+    assert_eq!(universe.get_cell(0, 1).unwrap(), Cell::Dead);
+    assert_eq!(universe.get_cell(1, 1).unwrap(), Cell::Alive);
+
+    let universe = Universe::new_pattern(5, 4, SeedPattern::Stripes).unwrap();
+    assert_eq!(universe.live_count(), 12);
+
+    let universe = Universe::new_pattern(5, 5, SeedPattern::Cross).unwrap();
+    assert_eq!(universe.live_count(), 9);
+    assert_eq!(universe.get_cell(2, 0).unwrap(), Cell::Alive);
+    assert_eq!(universe.get_cell(0, 2).unwrap(), Cell::Alive);
+
+    let universe = Universe::new_pattern(5, 6, SeedPattern::BorderRing).unwrap();
+    assert_eq!(universe.live_count(), 18);
+    assert_eq!(universe.get_cell(2, 2).unwrap(), Cell::Dead);
+}
+
+#[wasm_bindgen_test]
+pub fn test_ages() {
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3)]);
+    universe.insert_blinker(6, 5);
+
+    let ages = |universe: &Universe| {
+        let len = (universe.width() * universe.height()) as usize;
+        unsafe { std::slice::from_raw_parts(universe.ages(), len) }.to_vec()
+    };
+    assert!(ages(&universe).iter().all(|&age| age == 0));
+
+    for _ in 0..5 {
+        universe.tick();
+    }
+
+    let ages = ages(&universe);
+    for &(row, col) in &[(2, 2), (2, 3), (3, 2), (3, 3)] {
+        assert_eq!(ages[row * 8 + col], 5);
+    }
+    // The center of the blinker never dies, but its ends are reborn every
+    // generation.
+    assert_eq!(ages[6 * 8 + 5], 5);
+    assert_eq!(ages[5 * 8 + 5], 0);
+    assert_eq!(ages[6 * 8 + 4], 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_from_plaintext() {
+    let universe = Universe::from_plaintext("!Name: Blinker\n...\nOOO\n...\n").unwrap();
+    assert_eq!(universe.height(), 3);
+    assert_eq!(universe.width(), 3);
+    assert_eq!(universe.live_cells(), vec![3, 4, 5]);
+
+    // Short lines are padded with dead cells.
+    let universe = Universe::from_plaintext("!Name: Glider\n.O\n..*\n***").unwrap();
+    assert_eq!(universe.height(), 3);
+    assert_eq!(universe.width(), 3);
+    assert_eq!(universe.live_cells(), vec![1, 5, 6, 7, 8]);
+
+    let mut expected = Universe::new_dead(3, 3).unwrap();
+    expected.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    assert_eq!(universe.get_cells(), expected.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_malformed_plaintext_is_rejected() {
+    assert!(Universe::from_plaintext("").is_err());
+    assert!(Universe::from_plaintext("!Only a comment").is_err());
+    assert!(Universe::from_plaintext(".O.\n.X.").is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_reset_restores_initial_cells() {
+    let mut universe = Universe::new(12, 9).unwrap();
+    let initial = universe.get_cells();
+    let live_count = universe.live_count();
+
+    universe.tick_n(7);
+    universe.toggle_cell(3, 3).unwrap();
+    universe.resize(20, 20).unwrap();
+    universe.reset();
+
+    assert_eq!(universe.height(), 12);
+    assert_eq!(universe.width(), 9);
+    assert_eq!(universe.get_cells(), initial);
+    assert_eq!(universe.live_count(), live_count);
+    assert_eq!(universe.generation(), 0);
+
+    // Ticking from the reset board gives the same generations again.
+    let mut fresh = Universe::new(12, 9).unwrap();
+    fresh.tick_n(3);
+    universe.tick_n(3);
+    assert_eq!(universe.get_cells(), fresh.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_stats() {
+    let universe = Universe::new_dead(6, 5).unwrap();
+    let stats = universe.stats();
+    assert_eq!(stats.live_count, 0);
+    assert_eq!(stats.total_cells, 30);
+    assert_eq!(stats.density, 0.0);
+    assert_eq!(stats.generation, 0);
+
+    let mut universe = Universe::new_dead(6, 5).unwrap();
+    universe.set_region(0, 0, 6, 5, Cell::Alive);
+    assert_eq!(universe.stats().density, 1.0);
+
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.insert_blinker(3, 3);
+    universe.tick();
+    let stats = universe.stats();
+    assert_eq!(stats.live_count, 3);
+    assert_eq!(stats.density, 3.0 / 64.0);
+    assert_eq!(stats.generation, 1);
+}
+
+#[wasm_bindgen_test]
+pub fn test_profiling_does_not_change_ticks() {
+    let mut profiled = Universe::new(32, 32).unwrap();
+    profiled.set_profiling(true);
+    let mut plain = Universe::new(32, 32).unwrap();
+
+    for _ in 0..5 {
+        profiled.tick();
+        profiled.tick_incremental();
+        profiled.tick_n(2);
+        plain.tick();
+        plain.tick_incremental();
+        plain.tick_n(2);
+    }
+
+    assert_eq!(profiled.get_cells(), plain.get_cells());
+    assert_eq!(profiled.generation(), plain.generation());
+
+    profiled.set_profiling(false);
+    profiled.tick();
+    plain.tick();
+    assert_eq!(profiled.get_cells(), plain.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_glider_directions() {
+    // After four generations a glider is back in its original phase, moved
+    // one cell diagonally.
+    let cases = [
+        (GliderDirection::NE, -1, 1),
+        (GliderDirection::NW, -1, -1),
+        (GliderDirection::SE, 1, 1),
+        (GliderDirection::SW, 1, -1),
+    ];
+
+    for &(direction, d_row, d_col) in &cases {
+        let mut universe = Universe::new_dead(12, 12).unwrap();
+        universe.insert_glider(6, 6, direction);
+        universe.tick_n(4);
+
+        let mut expected = Universe::new_dead(12, 12).unwrap();
+        expected.insert_glider((6 + d_row) as u32, (6 + d_col) as u32, direction);
+
+        assert_eq!(
+            universe.get_cells(),
+            expected.get_cells(),
+            "{:?}",
+            direction
+        );
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_zero_size_universe_is_rejected() {
+    assert!(Universe::new(0, 5).is_err());
+    assert!(Universe::new(5, 0).is_err());
+    assert!(Universe::new_random(0, 0).is_err());
+    assert!(Universe::new_dead(0, 3).is_err());
+    assert!(Universe::new_pattern(3, 0, SeedPattern::Cross).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_returning_changes() {
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3)]);
+    assert_eq!(universe.tick_returning_changes(), 0);
+
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.insert_blinker(3, 3);
+    assert_eq!(universe.tick_returning_changes(), 4);
+    assert_eq!(universe.tick_returning_changes(), 4);
+    assert_eq!(universe.generation(), 2);
+}
+
+#[wasm_bindgen_test]
+pub fn test_stamp_matches_insert_glider() {
+    let glider = [1, 0, 0, 0, 1, 1, 1, 1, 0];
+
+    let mut universe = Universe::new_dead(10, 10).unwrap();
+    universe.stamp(4, 6, 3, 3, &glider, false).unwrap();
+    let mut expected = Universe::new_dead(10, 10).unwrap();
+    expected.insert_glider(5, 7, GliderDirection::SE);
+    assert_eq!(universe.get_cells(), expected.get_cells());
+
+    // Stamps wrap around the edges like the inserts do.
+    let mut universe = Universe::new_dead(10, 10).unwrap();
+    universe.stamp(9, 9, 3, 3, &glider, false).unwrap();
+    let mut expected = Universe::new_dead(10, 10).unwrap();
+    expected.insert_glider(0, 0, GliderDirection::SE);
+    assert_eq!(universe.get_cells(), expected.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_stamp_overwrite_clears_dead_cells() {
+    let mut universe = Universe::new_dead(6, 6).unwrap();
+    universe.set_region(0, 0, 6, 6, Cell::Alive);
+
+    universe.stamp(1, 1, 2, 2, &[1, 0, 0, 1], false).unwrap();
+    assert_eq!(universe.live_count(), 36);
+
+    universe.stamp(1, 1, 2, 2, &[1, 0, 0, 1], true).unwrap();
+    assert_eq!(universe.live_count(), 34);
+    assert_eq!(universe.get_cell(1, 2).unwrap(), Cell::Dead);
+    assert_eq!(universe.get_cell(2, 1).unwrap(), Cell::Dead);
+}
+
+#[wasm_bindgen_test]
+pub fn test_stamp_with_wrong_length_is_rejected() {
+    let mut universe = Universe::new_dead(6, 6).unwrap();
+    assert!(universe.stamp(0, 0, 3, 3, &[1, 1, 1], false).is_err());
+    assert_eq!(universe.live_count(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_sparse_tick_matches_reference() {
+    let mut universe = Universe::new_dead(64, 64).unwrap();
+    universe.insert_glider(5, 5, GliderDirection::SE);
+    universe.insert_lwss(40, 10);
+    universe.insert_blinker(20, 50);
+    let mut expected = universe.get_cells();
+
+    for _ in 0..100 {
+        universe.tick();
+        expected = reference_tick(64, 64, &expected);
+
+        assert_eq!(universe.get_cells(), expected);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_across_all_edges_matches_reference() {
+    let mut universe = Universe::new_dead(9, 11).unwrap();
+    // Blinkers straddling the top, bottom, left and right edges, plus a
+    // block split across all four corners.
+    universe.insert_blinker(0, 5);
+    universe.insert_blinker(8, 5);
+    universe.set_cells(&[(3, 0), (4, 0), (5, 0), (3, 10), (4, 10), (5, 10)]);
+    universe.set_cells(&[(0, 0), (0, 10), (8, 0), (8, 10)]);
+    let mut expected = universe.get_cells();
+
+    for _ in 0..30 {
+        universe.tick();
+        expected = reference_tick(9, 11, &expected);
+
+        assert_eq!(universe.get_cells(), expected);
+    }
+
+    // Resizing replaces both buffers, so ticks keep reading the right one.
+    universe.resize(11, 9).unwrap();
+    let mut expected = universe.get_cells();
+    for _ in 0..10 {
+        universe.tick();
+        expected = reference_tick(11, 9, &expected);
+
+        assert_eq!(universe.get_cells(), expected);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_to_rgba() {
+    let mut universe = Universe::new_dead(1, 2).unwrap();
+    universe.set_cells(&[(0, 1)]);
+
+    assert_eq!(
+        universe.to_rgba([0, 0, 0, 255], [255, 255, 255, 0]),
+        vec![255, 255, 255, 0, 0, 0, 0, 255]
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_to_rgba_js_packs_colors() {
+    let mut universe = Universe::new_dead(1, 2).unwrap();
+    universe.set_cells(&[(0, 0)]);
+
+    assert_eq!(
+        universe.to_rgba_js(0x1122_33ff, 0xaabb_cc00),
+        vec![0x11, 0x22, 0x33, 0xff, 0xaa, 0xbb, 0xcc, 0x00]
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_neighbor_counts() {
+    let universe = Universe::new(13, 17).unwrap();
+    let counts = universe.neighbor_counts();
+
+    assert_eq!(counts.len(), 13 * 17);
+    // Every live cell is a neighbor of eight others on a torus.
+    let total: u32 = counts.iter().map(|&count| count as u32).sum();
+    assert_eq!(total, 8 * universe.live_count());
+    assert_eq!(counts[2 * 17 + 5], universe.neighbor_count(2, 5).unwrap());
+}
+
+#[wasm_bindgen_test]
+pub fn test_von_neumann_neighborhood_excludes_diagonals() {
+    let mut universe = Universe::new_dead(5, 5).unwrap();
+    // All eight neighbors of (2, 2) are alive.
+    universe.set_region(1, 1, 3, 3, Cell::Alive);
+    universe.toggle_cell(2, 2).unwrap();
+    assert_eq!(universe.neighborhood(), Neighborhood::Moore);
+    assert_eq!(universe.neighbor_count(2, 2).unwrap(), 8);
+
+    universe.set_neighborhood(Neighborhood::VonNeumann);
+    assert_eq!(universe.neighbor_count(2, 2).unwrap(), 4);
+    assert_eq!(universe.neighbor_count(0, 0).unwrap(), 0);
+    assert_eq!(universe.neighbor_count(0, 1).unwrap(), 1);
+
+    // Under B1 the orthogonal neighbors of a lone cell are born; with the
+    // Moore neighborhood the diagonal ones would be too.
+    let mut universe = Universe::new_dead(5, 5).unwrap();
+    universe.set_neighborhood(Neighborhood::VonNeumann);
+    universe.set_rule("B1/S").unwrap();
+    universe.set_cells(&[(2, 2)]);
+    universe.tick();
+    assert_eq!(universe.live_cells(), vec![7, 11, 13, 17]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_von_neumann_ticks_agree() {
+    let mut universe = Universe::new(20, 20).unwrap();
+    universe.set_neighborhood(Neighborhood::VonNeumann);
+    universe.set_rule("B1/S012").unwrap();
+    let mut incremental = Universe::new(20, 20).unwrap();
+    incremental.set_neighborhood(Neighborhood::VonNeumann);
+    incremental.set_rule("B1/S012").unwrap();
+
+    for _ in 0..10 {
+        universe.tick();
+        incremental.tick_incremental();
+        assert_eq!(incremental.get_cells(), universe.get_cells());
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_hashed_matches_tick() {
+    let mut oscillator = Universe::new_dead(20, 20).unwrap();
+    oscillator.insert_pulsar(10, 10);
+
+    let mut spaceships = Universe::new_dead(24, 40).unwrap();
+    spaceships.insert_lwss(5, 5);
+    spaceships.insert_glider(15, 20, GliderDirection::NW);
+
+    let mut gun = Universe::new_dead(40, 50).unwrap();
+    gun.insert_gosper_glider_gun(2, 2);
+
+    let mut dense = Universe::new(31, 17).unwrap();
+    dense.set_rule("B36/S23").unwrap();
+
+    for universe in [oscillator, spaceships, gun, dense].iter_mut() {
+        let mut expected = Universe::deserialize(&universe.serialize()).unwrap();
+        expected.set_rule(&universe.rule()).unwrap();
+
+        for &steps in &[1, 2, 3, 7, 64, 100] {
+            universe.tick_hashed(steps);
+            expected.tick_n(steps);
+
+            assert_eq!(universe.get_cells(), expected.get_cells());
+            assert_eq!(universe.live_count(), expected.live_count());
+            assert_eq!(universe.generation(), expected.generation());
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_hashed_changed_cells_cover_the_jump() {
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.insert_blinker(3, 3);
+
+    universe.tick_hashed(2);
+    assert!(universe.changed_cells().is_empty());
+
+    universe.tick_hashed(1);
+    assert_eq!(
+        universe.changed_cells(),
+        vec![2 * 8 + 3, 3 * 8 + 2, 3 * 8 + 4, 4 * 8 + 3]
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_flat_index_access() {
+    let mut universe = Universe::new_dead(4, 6).unwrap();
+
+    for &(row, col) in &[(0, 0), (1, 2), (3, 5)] {
+        let idx = universe.get_index_pub(row, col).unwrap();
+        assert_eq!(idx, (row * 6 + col) as usize);
+
+        universe.set_cell_at_index(idx, Cell::Alive).unwrap();
+        assert_eq!(universe.cell_at_index(idx).unwrap(), Cell::Alive);
+        assert_eq!(universe.get_cell(row, col).unwrap(), Cell::Alive);
+    }
+    assert_eq!(universe.live_count(), 3);
+
+    universe.set_cell_at_index(8, Cell::Dead).unwrap();
+    assert_eq!(universe.cell_at_index(8).unwrap(), Cell::Dead);
+    assert_eq!(universe.live_count(), 2);
+
+    // The next tick sees the edits.
+    universe.tick();
+    assert_eq!(universe.live_count(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_out_of_range_flat_index_is_rejected() {
+    let mut universe = Universe::new_dead(4, 6).unwrap();
+
+    assert!(universe.get_index_pub(4, 0).is_err());
+    assert!(universe.cell_at_index(24).is_err());
+    assert!(universe.set_cell_at_index(24, Cell::Alive).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_toggle_cell_at_the_edges() {
+    let mut universe = Universe::new_dead(4, 6).unwrap();
+
+    universe.toggle_cell(3, 5).unwrap();
+    universe.toggle_cell(0, 5).unwrap();
+    universe.toggle_cell(3, 0).unwrap();
+    assert_eq!(universe.live_cells(), vec![5, 18, 23]);
+
+    universe.toggle_cell(3, 5).unwrap();
+    assert_eq!(universe.live_cells(), vec![5, 18]);
+    assert_eq!(universe.live_count(), 2);
+
+    // Both buffers are kept in sync, so the ticks after the edit agree with
+    // a universe built with the same cells from scratch.
+    let mut expected = Universe::new_dead(4, 6).unwrap();
+    expected.set_cells(&[(0, 5), (3, 0)]);
+    for _ in 0..3 {
+        universe.tick();
+        expected.tick();
+        assert_eq!(universe.get_cells(), expected.get_cells());
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_out_of_range_toggle_is_rejected() {
+    let mut universe = Universe::new_dead(4, 6).unwrap();
+
+    assert!(universe.toggle_cell(4, 0).is_err());
+    assert!(universe.toggle_cell(0, 6).is_err());
+    assert_eq!(universe.live_count(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_toggle_between_ticks_takes_effect() {
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    let mut incremental = Universe::new_dead(8, 8).unwrap();
+    universe.insert_blinker(3, 3);
+    incremental.insert_blinker(3, 3);
+    universe.tick();
+    incremental.tick_incremental();
+
+    // Turn the vertical blinker into a column of four, which becomes a
+    // 2x3 block of six cells in the next generation.
+    universe.toggle_cell(5, 3).unwrap();
+    incremental.toggle_cell(5, 3).unwrap();
+    let expected = reference_tick(8, 8, &universe.get_cells());
+
+    universe.tick();
+    incremental.tick_incremental();
+    assert_eq!(universe.get_cells(), expected);
+    assert_eq!(incremental.get_cells(), expected);
+    assert_eq!(universe.live_count(), 6);
+}
+
+#[wasm_bindgen_test]
+pub fn test_incremental_after_geometry_change() {
+    // Reflowing the cells keeps the same bits but moves the cells, so the
+    // neighbor counts from before can't be reused.
+    let mut universe = Universe::new_dead(6, 4).unwrap();
+    let mut incremental = Universe::new_dead(6, 4).unwrap();
+    universe.insert_blinker(2, 0);
+    incremental.insert_blinker(2, 0);
+    universe.tick();
+    incremental.tick_incremental();
+
+    universe.set_width_preserving(6).unwrap();
+    incremental.set_width_preserving(6).unwrap();
+    for _ in 0..3 {
+        universe.tick();
+        incremental.tick_incremental();
+        assert_eq!(incremental.live_cells(), universe.live_cells());
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_run_until_callback_reports_progress() {
+    let reports = Rc::new(RefCell::new(Vec::new()));
+    let recorder = reports.clone();
+    let callback = Closure::wrap(Box::new(move |generation: f64, live_count: u32| {
+        recorder.borrow_mut().push((generation, live_count));
+    }) as Box<dyn FnMut(f64, u32)>);
+    let callback = || -> js_sys::Function {
+        callback
+            .as_ref()
+            .unchecked_ref::<js_sys::Function>()
+            .clone()
+    };
+
+    // A still life is stable after a single generation.
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3)]);
+    assert_eq!(universe.run_until(1000, callback()), 1);
+    assert_eq!(*reports.borrow(), vec![(1.0, 4)]);
+
+    // A glider never settles down on a torus.
+    reports.borrow_mut().clear();
+    let mut universe = Universe::new_dead(16, 16).unwrap();
+    universe.insert_glider(5, 5, GliderDirection::SE);
+    assert_eq!(universe.run_until(200, callback()), 200);
+    assert_eq!(universe.generation(), 200);
+    assert_eq!(
+        *reports.borrow(),
+        vec![(64.0, 5), (128.0, 5), (192.0, 5), (200.0, 5)]
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_randomize_count() {
+    let mut universe = Universe::new(20, 30).unwrap();
+
+    universe.randomize_count(123, 7);
+    assert_eq!(universe.live_count(), 123);
+    assert_eq!(universe.live_cells().len(), 123);
+    let first = universe.get_cells();
+
+    universe.randomize_count(123, 7);
+    assert_eq!(universe.get_cells(), first);
+    universe.randomize_count(123, 8);
+    assert_ne!(universe.get_cells(), first);
+
+    universe.randomize_count(0, 7);
+    assert_eq!(universe.live_count(), 0);
+    universe.randomize_count(10_000, 7);
+    assert_eq!(universe.live_count(), 600);
+}
+
+#[wasm_bindgen_test]
+pub fn test_equals() {
+    let mut universe = Universe::new_dead(12, 10).unwrap();
+    let mut other = Universe::new_dead(12, 10).unwrap();
+    universe.randomize(0.4, 99);
+    other.randomize(0.4, 99);
+    assert!(universe.equals(&other));
+    assert!(other.equals(&universe));
+
+    other.toggle_cell(11, 9).unwrap();
+    assert!(!universe.equals(&other));
+
+    let mut transposed = Universe::new_dead(10, 12).unwrap();
+    assert!(!Universe::new_dead(12, 10).unwrap().equals(&transposed));
+    transposed.resize(12, 10).unwrap();
+    assert!(Universe::new_dead(12, 10).unwrap().equals(&transposed));
+}
+
+#[wasm_bindgen_test]
+pub fn test_find_predecessor() {
+    // Every 4x4 board and the board it evolves into.
+    let successors: Vec<(Vec<Cell>, Vec<Cell>)> = (0..1u32 << 16)
+        .map(|bits| {
+            let mut universe = Universe::new_dead(4, 4).unwrap();
+            let cells: Vec<(u32, u32)> = (0..16)
+                .filter(|bit| bits & (1 << bit) != 0)
+                .map(|bit| (bit / 4, bit % 4))
+                .collect();
+            universe.set_cells(&cells);
+            let before = universe.get_cells();
+            universe.tick();
+            (before, universe.get_cells())
+        })
+        .collect();
+
+    // A blinker is reachable from the blinker in its other phase.
+    let mut universe = Universe::new_dead(4, 4).unwrap();
+    universe.insert_blinker(1, 1);
+    let mut predecessor = universe.find_predecessor().unwrap();
+    predecessor.tick();
+    assert!(predecessor.equals(&universe));
+
+    // A board that no other board evolves into has no predecessor.
+    let garden_of_eden = (0..1u32 << 16)
+        .map(|bits| {
+            (0..16)
+                .map(|bit| Cell::from(bits & (1 << bit) != 0))
+                .collect::<Vec<Cell>>()
+        })
+        .find(|cells| successors.iter().all(|(_, next)| next != cells))
+        .unwrap();
+    let mut universe = Universe::new_dead(4, 4).unwrap();
+    let cells: Vec<(u32, u32)> = (0..16)
+        .filter(|&idx| garden_of_eden[idx as usize] == Cell::Alive)
+        .map(|idx| (idx / 4, idx % 4))
+        .collect();
+    universe.set_cells(&cells);
+    assert!(universe.find_predecessor().is_none());
+
+    // Too large to search.
+    assert!(Universe::new_dead(7, 7)
+        .unwrap()
+        .find_predecessor()
+        .is_none());
+}
+
+#[wasm_bindgen_test]
+pub fn test_rotate() {
+    // An L that looks different in every orientation.
+    let mut universe = Universe::new_dead(5, 3).unwrap();
+    universe.set_cells(&[(0, 0), (1, 0), (2, 0), (2, 1)]);
+    let original = universe.get_cells();
+
+    universe.rotate_cw();
+    assert_eq!((universe.height(), universe.width()), (3, 5));
+    assert_eq!(universe.live_cells(), vec![2, 3, 4, 7]);
+
+    universe.rotate_ccw();
+    assert_eq!((universe.height(), universe.width()), (5, 3));
+    assert_eq!(universe.get_cells(), original);
+
+    for _ in 0..4 {
+        universe.rotate_cw();
+    }
+    assert_eq!((universe.height(), universe.width()), (5, 3));
+    assert_eq!(universe.get_cells(), original);
+    assert_eq!(universe.live_count(), 4);
+}
+
+#[wasm_bindgen_test]
+pub fn test_flip() {
+    let mut universe = Universe::new_dead(6, 8).unwrap();
+    universe.insert_glider(1, 2, GliderDirection::SE);
+    let original = universe.get_cells();
+    let live_cells = |universe: &Universe| {
+        let mut cells: Vec<(u32, u32)> = universe
+            .live_cells()
+            .into_iter()
+            .map(|idx| (idx / universe.width(), idx % universe.width()))
+            .collect();
+        cells.sort_unstable();
+        cells
+    };
+    let glider = live_cells(&universe);
+
+    universe.flip_horizontal();
+    let mut expected: Vec<(u32, u32)> = glider.iter().map(|&(row, col)| (row, 7 - col)).collect();
+    expected.sort_unstable();
+    assert_eq!(live_cells(&universe), expected);
+
+    // A glider mirrored left to right travels southwest instead.
+    let mut southwest = Universe::new_dead(6, 8).unwrap();
+    southwest.insert_glider(1, 5, GliderDirection::SW);
+    assert!(universe.equals(&southwest));
+
+    universe.flip_horizontal();
+    assert_eq!(universe.get_cells(), original);
+
+    universe.flip_vertical();
+    let mut expected: Vec<(u32, u32)> = glider.iter().map(|&(row, col)| (5 - row, col)).collect();
+    expected.sort_unstable();
+    assert_eq!(live_cells(&universe), expected);
+
+    universe.flip_vertical();
+    assert_eq!(universe.get_cells(), original);
+}
+
+#[wasm_bindgen_test]
+pub fn test_shift() {
+    let mut universe = Universe::new_dead(5, 5).unwrap();
+    universe.set_cells(&[(4, 1), (4, 2), (4, 3)]);
+
+    universe.shift(1, 0);
+    let mut expected = Universe::new_dead(5, 5).unwrap();
+    expected.set_cells(&[(0, 1), (0, 2), (0, 3)]);
+    assert!(universe.equals(&expected));
+
+    universe.shift(-1, -2);
+    let mut expected = Universe::new_dead(5, 5).unwrap();
+    expected.set_cells(&[(4, 4), (4, 0), (4, 1)]);
+    assert!(universe.equals(&expected));
+    assert_eq!(universe.live_count(), 3);
+
+    // Both offsets are 2 modulo 5.
+    universe.shift(i32::MIN, i32::MAX);
+    let mut expected = Universe::new_dead(5, 5).unwrap();
+    expected.set_cells(&[(1, 1), (1, 2), (1, 3)]);
+    assert!(universe.equals(&expected));
+}
+
+#[wasm_bindgen_test]
+pub fn test_brians_brain() {
+    let mut universe = Universe::new_dead(6, 10).unwrap();
+    universe.set_boundary_mode(BoundaryMode::Dead);
+    universe.set_rule("B2/S").unwrap();
+    universe.set_states(3).unwrap();
+    assert_eq!(universe.states(), 3);
+
+    // Against the left edge a pair of live cells can only be born into on
+    // the right, leaving a dying pair behind: Brian's Brain's spaceship,
+    // which then moves one column right every tick.
+    universe.set_cells(&[(2, 0), (3, 0)]);
+    for generation in 1..8 {
+        universe.tick();
+
+        assert_eq!(
+            universe.live_cells(),
+            vec![2 * 10 + generation, 3 * 10 + generation]
+        );
+        for row in 2..4 {
+            assert_eq!(universe.cell_state(row, generation).unwrap(), 1);
+            assert_eq!(universe.cell_state(row, generation - 1).unwrap(), 2);
+            if generation > 1 {
+                assert_eq!(universe.cell_state(row, generation - 2).unwrap(), 0);
+            }
+        }
+    }
+    assert!(universe.cell_state(6, 0).is_err());
+    assert!(universe.cell_state(0, 10).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_cells_vec() {
+    let mut universe = Universe::new_dead(5, 7).unwrap();
+    universe.insert_glider(2, 3, GliderDirection::SE);
+
+    let cells = universe.cells_vec();
+    assert_eq!(cells.len(), 5 * 7);
+
+    let expected: Vec<u8> = universe
+        .get_cells()
+        .into_iter()
+        .map(|cell| cell as u8)
+        .collect();
+    assert_eq!(cells, expected);
+}
+
+#[wasm_bindgen_test]
+pub fn test_invert() {
+    // 7x11 doesn't fill the last word, whose spare bits must stay clear.
+    let mut universe = Universe::new_dead(7, 11).unwrap();
+    universe.insert_glider(3, 4, GliderDirection::SE);
+    let original = universe.get_cells();
+
+    universe.invert();
+    assert_eq!(universe.live_count(), 7 * 11 - 5);
+    assert_eq!(universe.live_cells().len(), 7 * 11 - 5);
+    assert!(!universe.is_alive(3, 4).unwrap());
+    assert!(universe.is_alive(0, 0).unwrap());
+
+    universe.invert();
+    assert_eq!(universe.get_cells(), original);
+    assert_eq!(universe.live_count(), 5);
+}
+
+#[wasm_bindgen_test]
+pub fn test_survives() {
+    // This soup dies out after 7 generations.
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.randomize(0.3, 13);
+    assert!(universe.survives(5));
+    assert!(!universe.survives(15));
+    assert_eq!(universe.generation(), 20);
+    assert_eq!(universe.live_count(), 0);
+
+    // While this one keeps going.
+    universe.randomize(0.3, 0);
+    assert!(universe.survives(200));
+}
+
+#[wasm_bindgen_test]
+pub fn test_new_random_with_density() {
+    let universe = Universe::new_random_with_density(16, 16, 0.0).unwrap();
+    assert_eq!(universe.live_count(), 0);
+
+    let universe = Universe::new_random_with_density(16, 16, 1.0).unwrap();
+    assert_eq!(universe.live_count(), 16 * 16);
+
+    // Out of range densities are clamped.
+    let universe = Universe::new_random_with_density(16, 16, -1.0).unwrap();
+    assert_eq!(universe.live_count(), 0);
+    let universe = Universe::new_random_with_density(16, 16, 2.0).unwrap();
+    assert_eq!(universe.live_count(), 16 * 16);
+}
+
+#[wasm_bindgen_test]
+pub fn test_still_lifes() {
+    let mut universe = Universe::new_dead(12, 12).unwrap();
+    universe.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)]);
+    universe.insert_blinker(8, 8);
+    let cells = universe.get_cells();
+
+    // Only the block is still; the blinker's middle cell stays alive, but
+    // cells are born around it.
+    assert_eq!(universe.still_lifes(), 1);
+    assert_eq!(universe.get_cells(), cells);
+    assert_eq!(universe.generation(), 0);
+
+    // A beehive is a second still life.
+    universe.set_cells(&[(5, 1), (5, 2), (6, 0), (6, 3), (7, 1), (7, 2)]);
+    assert_eq!(universe.still_lifes(), 2);
+
+    universe.clear();
+    assert_eq!(universe.still_lifes(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_json_round_trip() {
+    let mut universe = Universe::new_dead(6, 9).unwrap();
+    universe.insert_glider(2, 3, GliderDirection::SE);
+    universe.tick();
+    universe.tick();
+
+    let json = universe.to_json();
+    let restored = Universe::from_json(&json).unwrap();
+    assert!(restored.equals(&universe));
+    assert_eq!(restored.generation(), 2);
+    assert_eq!(restored.live_count(), 5);
+
+    let universe =
+        Universe::from_json(r#"{"width":3,"height":1,"generation":4,"cells":[0,1,0]}"#).unwrap();
+    assert_eq!(
+        universe.get_cells(),
+        vec![Cell::Dead, Cell::Alive, Cell::Dead]
+    );
+    assert_eq!(
+        universe.to_json(),
+        r#"{"width":3,"height":1,"generation":4,"cells":[0,1,0]}"#
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_from_json_rejected() {
+    let json = r#"{"width":3,"height":2,"generation":0,"cells":[0,1,0]}"#;
+    assert!(Universe::from_json(json).is_err());
+    let json = r#"{"width":3,"height":1,"generation":0,"cells":[0,2,0]}"#;
+    assert!(Universe::from_json(json).is_err());
+    assert!(Universe::from_json("not json").is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_named() {
+    let mut universe = Universe::new_dead(64, 64).unwrap();
+    universe.insert_named("glider", 5, 5).unwrap();
+    let mut expected = Universe::new_dead(64, 64).unwrap();
+    expected.insert_glider(5, 5, GliderDirection::SE);
+    assert!(universe.equals(&expected));
+
+    let mut universe = Universe::new_dead(64, 64).unwrap();
+    universe.insert_named("gun", 10, 10).unwrap();
+    let mut expected = Universe::new_dead(64, 64).unwrap();
+    expected.insert_gosper_glider_gun(10, 10);
+    assert!(universe.equals(&expected));
+
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.insert_named("Block", 3, 3).unwrap();
+    assert_eq!(universe.live_cells(), vec![27, 28, 35, 36]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_named_unknown_rejected() {
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    assert!(universe.insert_named("spaceship", 3, 3).is_err());
+    assert_eq!(universe.live_count(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_within() {
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.insert_blinker(3, 3);
+
+    // A clock that moves 1ms every time it is read.
+    let mut time = 0.0;
+    let clock = || {
+        time += 1.0;
+        time
+    };
+    assert_eq!(universe.tick_within_clock(5.0, clock), 4);
+    assert_eq!(universe.generation(), 4);
+
+    let mut time = 0.0;
+    let clock = || {
+        time += 1.0;
+        time
+    };
+    assert_eq!(universe.tick_within_clock(0.0, clock), 0);
+    assert_eq!(universe.generation(), 4);
+}
+
+#[wasm_bindgen_test]
+pub fn test_rows() {
+    let mut universe = Universe::new_dead(4, 6).unwrap();
+    universe.insert_blinker(2, 2);
+
+    let rows = universe.rows();
+    assert_eq!(rows.length(), 4);
+    for (row, cells) in rows.iter().enumerate() {
+        let cells: js_sys::Uint8Array = cells.dyn_into().unwrap();
+        assert_eq!(cells.length(), 6);
+
+        let expected: Vec<u8> = (0..6)
+            .map(|col| (row == 2 && (1..=3).contains(&col)) as u8)
+            .collect();
+        assert_eq!(cells.to_vec(), expected);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_birth_probability_zero() {
+    let mut universe = Universe::new_dead(10, 10).unwrap();
+    universe.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)]);
+    universe.insert_blinker(6, 5);
+    universe.set_birth_probability(0.0);
+
+    // The block survives as usual, but the blinker can't flip without
+    // births, so only its middle cell survives.
+    universe.tick();
+    let mut expected = Universe::new_dead(10, 10).unwrap();
+    expected.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2), (6, 5)]);
+    assert!(universe.equals(&expected));
+
+    universe.tick_incremental();
+    expected.toggle_cell(6, 5).unwrap();
+    assert!(universe.equals(&expected));
+
+    universe.set_birth_probability(1.0);
+    universe.insert_blinker(6, 5);
+    universe.tick();
+    assert!(universe.is_alive(5, 5).unwrap());
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_cells_format() {
+    let glider =
+        "!Name: Glider\n!Author: Richard K. Guy\n!The smallest spaceship.\n.O.\n..O\nOOO\n";
+
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.insert_cells_format(2, 3, glider).unwrap();
+    let mut expected = Universe::new_dead(8, 8).unwrap();
+    expected.set_cells(&[(2, 4), (3, 5), (4, 3), (4, 4), (4, 5)]);
+    assert!(universe.equals(&expected));
+
+    // Wraps around the bottom-right corner.
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.insert_cells_format(6, 6, glider).unwrap();
+    let mut expected = Universe::new_dead(8, 8).unwrap();
+    expected.set_cells(&[(6, 7), (7, 0), (0, 6), (0, 7), (0, 0)]);
+    assert!(universe.equals(&expected));
+}
+
+#[wasm_bindgen_test]
+pub fn test_population_history() {
+    let mut universe = Universe::new_dead(16, 16).unwrap();
+    universe.set_cells(&[(5, 6), (5, 7), (6, 5), (6, 6), (7, 6)]);
+    universe.set_track_population(true);
+
+    let mut expected = Vec::new();
+    for _ in 0..5 {
+        universe.tick();
+        expected.push(universe.live_count());
+    }
+    assert_eq!(universe.population_history(), expected);
+
+    universe.set_population_limit(2);
+    assert_eq!(universe.population_history(), expected[3..].to_vec());
+    universe.tick();
+    assert_eq!(
+        universe.population_history(),
+        vec![expected[4], universe.live_count()]
+    );
+
+    universe.set_track_population(false);
+    universe.tick();
+    assert!(universe.population_history().is_empty());
+}
+
+#[wasm_bindgen_test]
+pub fn test_cylinder_boundaries() {
+    let mut universe = Universe::new_dead(12, 12).unwrap();
+    universe.set_boundary_x(BoundaryMode::Toroidal);
+    universe.set_boundary_y(BoundaryMode::Dead);
+    assert_eq!(universe.boundary_x(), BoundaryMode::Toroidal);
+    assert_eq!(universe.boundary_y(), BoundaryMode::Dead);
+    assert_eq!(universe.boundary_mode(), BoundaryMode::Toroidal);
+
+    // A glider flying north-east wraps from the right edge onto the left,
+    // but can't get past the dead top edge and never reappears at the
+    // bottom. It crashes into a block straddling the left and right edges.
+    universe.insert_glider(4, 8, GliderDirection::NE);
+    for _ in 0..40 {
+        universe.tick();
+        assert!(universe.live_cells().iter().all(|&idx| idx / 12 < 6));
+    }
+    assert_eq!(universe.live_cells(), vec![0, 11, 12, 23]);
+
+    // On a torus it keeps flying.
+    let mut universe = Universe::new_dead(12, 12).unwrap();
+    universe.insert_glider(4, 8, GliderDirection::NE);
+    universe.tick_n(40);
+    assert_eq!(universe.live_count(), 5);
+
+    universe.set_boundary_mode(BoundaryMode::Mirror);
+    assert_eq!(universe.boundary_x(), BoundaryMode::Mirror);
+    assert_eq!(universe.boundary_y(), BoundaryMode::Mirror);
+}
+
+#[wasm_bindgen_test]
+pub fn test_center_live_cells() {
+    let mut universe = Universe::new_dead(7, 9).unwrap();
+    universe.insert_blinker(1, 6);
+
+    universe.center_live_cells();
+    let mut expected = Universe::new_dead(7, 9).unwrap();
+    expected.insert_blinker(3, 4);
+    assert!(universe.equals(&expected));
+
+    // Already centered.
+    universe.center_live_cells();
+    assert!(universe.equals(&expected));
+
+    let mut universe = Universe::new_dead(7, 9).unwrap();
+    universe.center_live_cells();
+    assert_eq!(universe.live_count(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_cells_len() {
+    let mut universe = Universe::new_dead(5, 7).unwrap();
+    assert_eq!(universe.cells_len(), 5 * 7);
+
+    universe.resize(3, 4).unwrap();
+    assert_eq!(universe.cells_len(), 3 * 4);
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_glider_stream() {
+    let mut universe = Universe::new_dead(64, 64).unwrap();
+    universe.insert_glider_stream(2, 2, 10, 4);
+    assert_eq!(universe.live_count(), 5 * 10);
+
+    let mut expected = Universe::new_dead(64, 64).unwrap();
+    for i in 0..10 {
+        expected.insert_glider(2 + 4 * i, 2 + 4 * i, GliderDirection::SE);
+    }
+    assert!(universe.equals(&expected));
+
+    // Wraps around the bottom-right corner.
+    let mut universe = Universe::new_dead(16, 16).unwrap();
+    universe.insert_glider_stream(8, 8, 3, 5);
+    assert_eq!(universe.live_count(), 5 * 3);
+    assert!(universe.is_alive(2, 2).unwrap());
+}
+
+#[wasm_bindgen_test]
+pub fn test_preview_next() {
+    let mut universe = Universe::new_dead(6, 6).unwrap();
+    universe.insert_blinker(2, 2);
+    let cells = universe.get_cells();
+
+    let preview = universe.preview_next();
+    assert_eq!(universe.get_cells(), cells);
+    assert_eq!(universe.generation(), 0);
+
+    universe.tick();
+    assert_eq!(preview, universe.cells_vec());
+    assert_ne!(universe.get_cells(), cells);
+}
+
+#[wasm_bindgen_test]
+pub fn test_connected_region() {
+    let mut universe = Universe::new_dead(6, 8).unwrap();
+    universe.set_cells(&[(2, 3), (2, 4), (3, 3), (3, 4)]);
+    for &(row, col) in &[(2, 3), (2, 4), (3, 3), (3, 4)] {
+        assert_eq!(
+            universe.connected_region(row, col).unwrap(),
+            vec![19, 20, 27, 28]
+        );
+    }
+
+    // Everything else is one dead region.
+    let dead = universe.connected_region(0, 0).unwrap();
+    assert_eq!(dead.len(), 6 * 8 - 4);
+    assert!(!dead.contains(&19));
+
+    // Two live columns split the dead cells in two, one of which wraps
+    // around the left and right edges.
+    let mut universe = Universe::new_dead(4, 8).unwrap();
+    let walls: Vec<(u32, u32)> = (0..4).flat_map(|row| vec![(row, 2), (row, 5)]).collect();
+    universe.set_cells(&walls);
+    let expected: Vec<u32> = (0..4)
+        .flat_map(|row| vec![row * 8, row * 8 + 1, row * 8 + 6, row * 8 + 7])
+        .collect();
+    assert_eq!(universe.connected_region(1, 7).unwrap(), expected);
+    assert_eq!(universe.connected_region(0, 3).unwrap().len(), 8);
+
+    // Diagonal neighbors are connected.
+    let mut universe = Universe::new_dead(6, 6).unwrap();
+    universe.set_cells(&[(1, 1), (2, 2)]);
+    assert_eq!(universe.connected_region(1, 1).unwrap(), vec![7, 14]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_connected_region_out_of_bounds_rejected() {
+    let universe = Universe::new_dead(6, 6).unwrap();
+    assert!(universe.connected_region(6, 0).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_escaping_gliders() {
+    // The gun fires a glider every 30 generations, the first one getting
+    // clear of it after about 15.
+    let mut universe = Universe::new_dead(80, 80).unwrap();
+    universe.insert_gosper_glider_gun(1, 1);
+    let gliders = universe.escaping_gliders(120);
+    assert!((3..=5).contains(&gliders), "counted {} gliders", gliders);
+    assert_eq!(universe.generation(), 120);
+
+    // A lone glider is counted once, however long it flies.
+    let mut universe = Universe::new_dead(30, 30).unwrap();
+    universe.insert_glider(5, 5, GliderDirection::SE);
+    assert_eq!(universe.escaping_gliders(200), 1);
+
+    let mut universe = Universe::new_dead(30, 30).unwrap();
+    universe.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)]);
+    universe.insert_pulsar(15, 15);
+    assert_eq!(universe.escaping_gliders(30), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_base64_round_trip() {
+    let mut universe = Universe::new_dead(9, 13).unwrap();
+    universe.insert_glider(3, 4, GliderDirection::SE);
+    universe.tick();
+
+    let encoded = universe.to_base64();
+    assert!(encoded
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+    let restored = Universe::from_base64(&encoded).unwrap();
+    assert!(restored.equals(&universe));
+    assert_eq!(restored.generation(), 1);
+}
+
+#[wasm_bindgen_test]
+pub fn test_from_base64_corrupted_rejected() {
+    let mut universe = Universe::new_dead(9, 13).unwrap();
+    universe.insert_glider(3, 4, GliderDirection::SE);
+    let encoded = universe.to_base64();
+
+    assert!(Universe::from_base64(&encoded[..encoded.len() - 4]).is_err());
+    assert!(Universe::from_base64(&encoded.replacen('A', "!", 1)).is_err());
+    assert!(Universe::from_base64(&encoded[4..]).is_err());
+
+    // A 65536x65537 header followed by the 8 KiB its size wraps around to
+    // in 32-bit arithmetic.
+    let oversized = format!("R09MAAEAAAEAAQABAAAAAAAAAAAA{}", "A".repeat(10923));
+    assert!(Universe::from_base64(&oversized).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_new_from_fn_callback() {
+    let callback = Closure::wrap(Box::new(|idx: u32| idx % 2 == 0) as Box<dyn FnMut(u32) -> bool>);
+    let callback = callback
+        .as_ref()
+        .unchecked_ref::<js_sys::Function>()
+        .clone();
+
+    let universe = Universe::new_from_fn(3, 5, callback).unwrap();
+    let expected: Vec<Cell> = (0..15)
+        .map(|idx| {
+            if idx % 2 == 0 {
+                Cell::Alive
+            } else {
+                Cell::Dead
+            }
+        })
+        .collect();
+    assert_eq!(universe.get_cells(), expected);
+    assert_eq!(universe.live_count(), 8);
+
+    // Any truthy value brings a cell to life.
+    let callback = js_sys::Function::new_with_args("idx", "return idx === 4 ? 'yes' : 0;");
+    let universe = Universe::new_from_fn(3, 5, callback).unwrap();
+    assert_eq!(universe.live_cells(), vec![4]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_new_from_fn_callback_error_rejected() {
+    let callback = js_sys::Function::new_with_args("idx", "throw new Error('no cells here');");
+    assert!(Universe::new_from_fn(3, 5, callback).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_set_width_preserving() {
+    let mut universe = Universe::new_dead(4, 6).unwrap();
+    universe.set_cells(&[(0, 5), (1, 0), (2, 3), (3, 5)]);
+    let live_cells = universe.live_cells();
+
+    universe.set_width_preserving(8).unwrap();
+    assert_eq!((universe.height(), universe.width()), (3, 8));
+    assert_eq!(universe.live_cells(), live_cells);
+    assert!(universe.is_alive(0, 6).unwrap());
+    assert!(universe.is_alive(2, 7).unwrap());
+
+    // 24 cells don't fill 5 rows of 5, so the last row is padded.
+    universe.set_width_preserving(5).unwrap();
+    assert_eq!((universe.height(), universe.width()), (5, 5));
+    assert_eq!(universe.live_cells(), live_cells);
+    assert!(!universe.is_alive(4, 4).unwrap());
+    assert_eq!(universe.live_count(), 4);
+
+    assert!(universe.set_width_preserving(0).is_err());
+    assert_eq!((universe.height(), universe.width()), (5, 5));
+    assert_eq!(universe.live_cells(), live_cells);
+}
+
+#[wasm_bindgen_test]
+pub fn test_insert_wrap_disabled() {
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.set_insert_wrap(false);
+
+    // Only the glider's cells at or below and right of (0, 0) are kept.
+    universe.insert_glider(0, 0, GliderDirection::SE);
+    let mut expected = Universe::new_dead(8, 8).unwrap();
+    expected.set_cells(&[(0, 0), (0, 1), (1, 0)]);
+    assert!(universe.equals(&expected));
+
+    universe.set_cells(&[(9, 3)]);
+    universe.insert_rle(6, 6, "3o!").unwrap();
+    universe.stamp(7, 7, 1, 2, &[1, 1], false).unwrap();
+    expected.set_cells(&[(6, 6), (6, 7), (7, 7)]);
+    assert!(universe.equals(&expected));
+
+    // Ticks still wrap: a blinker on the top edge flips across it.
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.set_insert_wrap(false);
+    universe.insert_blinker(0, 4);
+    universe.tick();
+    let mut expected = Universe::new_dead(8, 8).unwrap();
+    expected.set_cells(&[(7, 4), (0, 4), (1, 4)]);
+    assert!(universe.equals(&expected));
+}
+
+#[wasm_bindgen_test]
+pub fn test_activity() {
+    let mut universe = Universe::new_dead(64, 64).unwrap();
+    universe.randomize(0.5, 3);
+    assert_eq!(universe.activity(), 0);
+    let before = universe.get_cells();
+    universe.tick();
+    let distance = before
+        .iter()
+        .zip(universe.get_cells())
+        .filter(|&(before, after)| *before != after)
+        .count() as u32;
+    assert_eq!(universe.activity(), distance);
+    assert!(universe.activity() > 64 * 64 / 4);
+
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)]);
+    universe.tick();
+    assert_eq!(universe.activity(), 0);
+
+    // Dying cells in Generations rules don't count.
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.set_rule("B2/S").unwrap();
+    universe.set_states(3).unwrap();
+    universe.set_cells(&[(2, 2), (3, 2)]);
+    universe.tick();
+    universe.tick();
+    assert!(universe.changed_cells().len() as u32 > universe.activity());
+}
+
+#[wasm_bindgen_test]
+pub fn test_weights() {
+    // Weights of all 1 are plain Conway.
+    let mut conway = Universe::new_dead(32, 32).unwrap();
+    conway.randomize(0.4, 11);
+    let mut weighted = Universe::new_dead(32, 32).unwrap();
+    weighted.randomize(0.4, 11);
+    weighted.set_weights(&[1; 8]).unwrap();
+    for _ in 0..20 {
+        conway.tick();
+        weighted.tick();
+        assert!(weighted.equals(&conway));
+    }
+
+    // Counting only the orthogonal neighbors twice: a block's cells have two
+    // orthogonal and one diagonal neighbor each, 4 in total, so they die.
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3)]);
+    universe.set_weights(&[0, 2, 0, 2, 2, 0, 2, 0]).unwrap();
+    assert_eq!(universe.neighbor_count(2, 2).unwrap(), 4);
+    assert_eq!(universe.neighbor_counts()[2 * 8 + 2], 4);
+    universe.tick();
+    assert_eq!(universe.live_count(), 0);
+
+    // A domino's cells have a single orthogonal neighbor worth 2, so it
+    // survives, and copies of the universe keep the weights.
+    let mut universe = Universe::new_dead(4, 4).unwrap();
+    universe.set_cells(&[(1, 1), (1, 2)]);
+    universe.set_weights(&[0, 2, 0, 2, 2, 0, 2, 0]).unwrap();
+    let mut predecessor = universe.find_predecessor().unwrap();
+    predecessor.tick();
+    assert!(predecessor.equals(&universe));
+    let subgrid = universe.subgrid(0, 0, 3, 4, false).unwrap();
+    assert_eq!(subgrid.neighbor_count(1, 1).unwrap(), 2);
+
+    // A range rule sees sums above 8: with every weight 2, the cell with
+    // five live neighbors has a sum of 10 and is born.
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.set_cells(&[(0, 0), (0, 1), (0, 2), (1, 0), (1, 2)]);
+    universe.set_weights(&[2; 8]).unwrap();
+    universe.set_range_rule("B10/S16").unwrap();
+    universe.tick();
+    assert_eq!(universe.live_cells(), vec![9]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_set_weights_wrong_length_rejected() {
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    assert!(universe.set_weights(&[1; 7]).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_range_one_matches_neighbor_count() {
+    for &mode in &[
+        BoundaryMode::Toroidal,
+        BoundaryMode::Dead,
+        BoundaryMode::Mirror,
+    ] {
+        let mut universe = Universe::new_dead(16, 12).unwrap();
+        universe.randomize(0.4, 5);
+        universe.set_boundary_mode(mode);
+        let counts = universe.neighbor_counts();
+
+        universe.set_range(2).unwrap();
+        universe.set_range(1).unwrap();
+        assert_eq!(universe.neighbor_counts(), counts);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_range_two() {
+    // A single live cell is a neighbor of the 24 cells around it, wrapping
+    // around the corner.
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.set_cells(&[(0, 0)]);
+    universe.set_range(2).unwrap();
+    let counts = universe.neighbor_counts();
+    assert_eq!(counts.iter().filter(|&&count| count == 1).count(), 24);
+    assert_eq!(universe.neighbor_count(6, 6).unwrap(), 1);
+    assert_eq!(universe.neighbor_count(0, 0).unwrap(), 0);
+    assert_eq!(universe.neighbor_count(5, 0).unwrap(), 0);
+
+    // A 3x3 block under B5..9/S8..24: every cell has 8 live neighbors and
+    // survives. The dead cells beside its edges have 6 and are born, while
+    // those off its corners have 4 and those two rows away 3.
+    universe.clear();
+    universe.set_region(3, 3, 3, 3, Cell::Alive);
+    universe.set_range_rule("B5..9/S8..24").unwrap();
+    universe.tick();
+    assert!(universe.is_alive(2, 4).unwrap());
+    assert!(!universe.is_alive(2, 2).unwrap());
+    assert!(universe.is_alive(4, 4).unwrap());
+    assert!(!universe.is_alive(1, 4).unwrap());
+}
+
+#[wasm_bindgen_test]
+pub fn test_set_range_out_of_bounds_rejected() {
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    assert!(universe.set_range(0).is_err());
+    assert!(universe.set_range(8).is_err());
+    assert!(universe.set_range_rule("B5..3/S2").is_err());
+    assert!(universe.set_range_rule("B3..5").is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_range_rule_round_trips_through_rle() {
+    let mut universe = Universe::new_dead(16, 16).unwrap();
+    universe.set_range(2).unwrap();
+    universe.set_range_rule("B5..9/S8..24").unwrap();
+    universe.set_region(6, 6, 3, 3, Cell::Alive);
+    assert_eq!(universe.rule(), "B5..9/S8..24");
+
+    let rle = universe.to_rle();
+    let header = rle.lines().next().unwrap();
+    assert_eq!(header, "x = 3, y = 3, rule = B5..9/S8..24");
+
+    // The rule from the header runs the same automaton.
+    let mut imported = Universe::new_dead(16, 16).unwrap();
+    imported.set_range(2).unwrap();
+    imported
+        .set_range_rule(header.split("rule = ").nth(1).unwrap())
+        .unwrap();
+    imported.insert_rle(6, 6, &rle).unwrap();
+    for _ in 0..4 {
+        universe.tick();
+        imported.tick();
+        assert!(imported.equals(&universe));
+    }
+
+    universe.set_rule("B36/S23").unwrap();
+    assert_eq!(universe.rule(), "B36/S23");
+}
+
+#[wasm_bindgen_test]
+pub fn test_to_pbm() {
+    let mut universe = Universe::new_dead(3, 4).unwrap();
+    universe.insert_blinker(1, 1);
+    let pbm = universe.to_pbm();
+    let mut lines = pbm.lines();
+    assert_eq!(lines.next(), Some("P1"));
+    assert_eq!(lines.next(), Some("4 3"));
+    let bits: String = lines.collect();
+    assert_eq!(bits, "000011100000");
+    assert_eq!(bits.matches('1').count() as u32, universe.live_count());
+
+    // Long rows are split to keep lines within 70 characters.
+    let universe = Universe::new_dead(2, 100).unwrap();
+    let pbm = universe.to_pbm();
+    assert!(pbm.lines().all(|line| line.len() <= 70));
+    assert_eq!(pbm.lines().skip(2).map(str::len).sum::<usize>(), 200);
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_events() {
+    let mut universe = Universe::new_dead(5, 5).unwrap();
+    universe.insert_blinker(2, 2);
+    for _ in 0..4 {
+        let events = universe.tick_events();
+        assert_eq!(events.len(), 25);
+        assert_eq!(events.iter().filter(|&&event| event == 1).count(), 2);
+        assert_eq!(events.iter().filter(|&&event| event == -1).count(), 2);
+        // The center cell, at index 12, stays alive.
+        assert_eq!(events[12], 0);
+    }
+
+    // The horizontal blinker turns vertical.
+    let events = universe.tick_events();
+    assert_eq!(events[7], 1);
+    assert_eq!(events[11], -1);
+}
+
+#[wasm_bindgen_test]
+pub fn test_seed_rng() {
+    seed_rng(42);
+    let first = Universe::new_random(32, 32).unwrap();
+    let next = Universe::new_random(32, 32).unwrap();
+    assert!(!next.equals(&first));
+
+    seed_rng(42);
+    let second = Universe::new_random(32, 32).unwrap();
+    assert!(second.equals(&first));
+
+    seed_rng(7);
+    let other = Universe::new_random(32, 32).unwrap();
+    assert!(!other.equals(&first));
+}
+
+#[wasm_bindgen_test]
+pub fn test_seed_rng_stochastic_births() {
+    let run = |seed: u64| {
+        seed_rng(seed);
+        let mut universe = Universe::new_dead(32, 32).unwrap();
+        universe.randomize(0.4, 5);
+        universe.set_birth_probability(0.5);
+        universe.tick_n(10);
+        universe.live_cells()
+    };
+
+    assert_eq!(run(42), run(42));
+    assert_ne!(run(42), run(7));
+}
+
+#[wasm_bindgen_test]
+pub fn test_cell_history() {
+    let mut universe = Universe::new_dead(5, 5).unwrap();
+    universe.insert_blinker(2, 2);
+
+    // The center of a blinker stays alive, while its ends blink.
+    universe.watch_cell(2, 2).unwrap();
+    universe.tick_n(4);
+    assert_eq!(universe.cell_history(), vec![1, 1, 1, 1, 1]);
+
+    universe.watch_cell(2, 1).unwrap();
+    universe.tick_n(4);
+    assert_eq!(universe.cell_history(), vec![1, 0, 1, 0, 1]);
+
+    universe.watch_cell(1, 2).unwrap();
+    universe.tick();
+    assert_eq!(universe.cell_history(), vec![0, 1]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_apply_cells() {
+    let coords = [(1, 1), (1, 2), (2, 3), (9, 0)];
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.apply_cells(&coords, Cell::Alive);
+    assert_eq!(universe.live_count(), 4);
+    assert!(universe.is_alive(1, 0).unwrap());
+
+    universe.apply_cells(&coords, Cell::Dead);
+    assert_eq!(universe.live_count(), 0);
+    assert!(universe.equals(&Universe::new_dead(8, 8).unwrap()));
+
+    // Both buffers were cleared, so nothing comes back on the next tick.
+    universe.tick();
+    assert_eq!(universe.live_count(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_auto_expand() {
+    let mut universe = Universe::new_dead(12, 12).unwrap();
+    universe.set_auto_expand(true);
+    universe.insert_glider(8, 8, GliderDirection::SE);
+
+    // The glider reaches the bottom right corner and, instead of wrapping
+    // around, flies on into the new space.
+    universe.tick_n(40);
+    assert!(universe.height() > 12 && universe.width() > 12);
+    assert_eq!(universe.live_count(), 5);
+    let (min_row, min_col, max_row, max_col) = universe.live_bounds().unwrap();
+    assert_eq!((max_row - min_row, max_col - min_col), (2, 2));
+    assert!(min_row >= 12 && min_col >= 12);
+
+    // Without it the glider wraps around to the top left.
+    let mut universe = Universe::new_dead(12, 12).unwrap();
+    universe.insert_glider(8, 8, GliderDirection::SE);
+    universe.tick_n(40);
+    assert_eq!((universe.height(), universe.width()), (12, 12));
+    let (min_row, min_col, _, _) = universe.live_bounds().unwrap();
+    assert!(min_row < 8 && min_col < 8);
+}
+
+#[wasm_bindgen_test]
+pub fn test_auto_expand_keeps_cell_state() {
+    let mut universe = Universe::new_dead(12, 12).unwrap();
+    universe.set_auto_expand(true);
+    universe.set_immigration_mode(true);
+    universe.set_history_capacity(4);
+    universe.insert_glider(8, 8, GliderDirection::SE);
+    for row in 8..11 {
+        for col in 8..11 {
+            if universe.is_alive(row, col).unwrap() {
+                universe.set_cell_species(row, col, 2).unwrap();
+            }
+        }
+    }
+
+    // The glider keeps its species as the universe grows around it.
+    universe.tick_n(12);
+    assert!(universe.height() > 12 && universe.width() > 12);
+    let species = species(&universe);
+    assert_eq!(species.iter().filter(|&&species| species == 2).count(), 5);
+    assert_eq!(species.iter().filter(|&&species| species == 1).count(), 0);
+
+    // The generations before the growth can still be stepped back to.
+    for _ in 0..4 {
+        assert!(universe.step_back());
+        assert_eq!(universe.live_count(), 5);
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_render_bordered() {
+    let mut universe = Universe::new_dead(3, 4).unwrap();
+    universe.insert_blinker(1, 1);
+    let rendered = universe.render_bordered();
+    assert_eq!(rendered, "┌────┐\n│◻◻◻◻│\n│◼◼◼◻│\n│◻◻◻◻│\n└────┘\n");
+    assert_eq!(rendered.lines().count(), 5);
+    assert_eq!(rendered.matches('─').count(), 8);
+    assert_eq!(rendered.matches('│').count(), 6);
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_lut_matches_tick() {
+    for &(mode, neighborhood) in &[
+        (BoundaryMode::Toroidal, Neighborhood::Moore),
+        (BoundaryMode::Dead, Neighborhood::Moore),
+        (BoundaryMode::Mirror, Neighborhood::VonNeumann),
+    ] {
+        let mut expected = Universe::new_dead(40, 56).unwrap();
+        expected.randomize(0.5, 17);
+        expected.set_boundary_mode(mode);
+        expected.set_neighborhood(neighborhood);
+        let mut universe = Universe::new_dead(40, 56).unwrap();
+        universe.randomize(0.5, 17);
+        universe.set_boundary_mode(mode);
+        universe.set_neighborhood(neighborhood);
+
+        for _ in 0..50 {
+            expected.tick();
+            universe.tick_lut();
+            assert!(universe.equals(&expected));
+            assert_eq!(universe.live_count(), expected.live_count());
+            assert_eq!(universe.changed_cells(), expected.changed_cells());
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_subgrid() {
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.insert_blinker(4, 4);
+    let subgrid = universe.subgrid(3, 3, 3, 3, false).unwrap();
+    assert_eq!((subgrid.height(), subgrid.width()), (3, 3));
+    assert_eq!(subgrid.cells_vec(), vec![0, 0, 0, 1, 1, 1, 0, 0, 0]);
+    assert_eq!(universe.live_count(), 3);
+
+    // A block past the bottom right corner is padded with dead cells, or
+    // wraps around to the top left.
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    universe.set_cells(&[(0, 0), (7, 7)]);
+    let padded = universe.subgrid(7, 7, 2, 2, false).unwrap();
+    assert_eq!(padded.cells_vec(), vec![1, 0, 0, 0]);
+    let wrapped = universe.subgrid(7, 7, 2, 2, true).unwrap();
+    assert_eq!(wrapped.cells_vec(), vec![1, 0, 0, 1]);
+}
+
+/// A 4x4 universe with a blinker on its second row, and a 2x2 block to
+/// paste over its left end.
+fn paste_fixture() -> (Universe, Universe) {
+    let mut universe = Universe::new_dead(4, 4).unwrap();
+    universe.set_cells(&[(1, 0), (1, 1), (1, 2)]);
+    let mut block = Universe::new_dead(2, 2).unwrap();
+    block.set_cells(&[(0, 0), (1, 1)]);
+    (universe, block)
+}
+
+#[wasm_bindgen_test]
+pub fn test_paste_or() {
+    let (mut universe, block) = paste_fixture();
+    universe.paste(0, 0, &block, PasteMode::Or);
+    assert_eq!(
+        universe.cells_vec(),
+        vec![1, 0, 0, 0, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(universe.live_count(), 4);
+}
+
+#[wasm_bindgen_test]
+pub fn test_paste_replace() {
+    let (mut universe, block) = paste_fixture();
+    universe.paste(0, 0, &block, PasteMode::Replace);
+    assert_eq!(
+        universe.cells_vec(),
+        vec![1, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(universe.live_count(), 3);
+}
+
+#[wasm_bindgen_test]
+pub fn test_paste_xor() {
+    let (mut universe, block) = paste_fixture();
+    universe.paste(0, 0, &block, PasteMode::Xor);
+    assert_eq!(
+        universe.cells_vec(),
+        vec![1, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(universe.live_count(), 3);
+
+    // Pasting wraps around the edges.
+    let (mut universe, block) = paste_fixture();
+    universe.paste(3, 3, &block, PasteMode::Xor);
+    assert!(universe.is_alive(3, 3).unwrap());
+    assert!(universe.is_alive(0, 0).unwrap());
+    assert_eq!(universe.live_count(), 5);
+}
+
+fn species(universe: &Universe) -> Vec<u8> {
+    let len = (universe.width() * universe.height()) as usize;
+    unsafe { std::slice::from_raw_parts(universe.species(), len) }.to_vec()
+}
+
+#[wasm_bindgen_test]
+pub fn test_immigration_single_species() {
+    let mut universe = Universe::new_dead(32, 32).unwrap();
+    universe.randomize(0.4, 23);
+    universe.set_immigration_mode(true);
+    for _ in 0..20 {
+        universe.tick();
+        let species = species(&universe);
+        for (idx, &species) in species.iter().enumerate() {
+            let alive = universe.cell_at_index(idx).unwrap() == Cell::Alive;
+            assert_eq!(species, alive as u8);
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_immigration_mixed_species() {
+    // A blinker with two cells of the second species: the cells born next
+    // to it take the majority species, and the center survives as it was.
+    let mut universe = Universe::new_dead(5, 5).unwrap();
+    universe.insert_blinker(2, 2);
+    universe.set_immigration_mode(true);
+    universe.set_cell_species(2, 1, 2).unwrap();
+    universe.set_cell_species(2, 2, 2).unwrap();
+    universe.tick();
+    let after = species(&universe);
+    assert_eq!((after[7], after[12], after[17]), (2, 2, 2));
+    assert_eq!(after.iter().filter(|&&species| species != 0).count(), 3);
+
+    // Where two species meet, both live on side by side.
+    let mut universe = Universe::new_dead(32, 32).unwrap();
+    universe.randomize(0.4, 29);
+    universe.set_immigration_mode(true);
+    for row in 0..32 {
+        for col in 16..32 {
+            if universe.is_alive(row, col).unwrap() {
+                universe.set_cell_species(row, col, 2).unwrap();
+            }
+        }
+    }
+    universe.tick_n(10);
+    let after = species(&universe);
+    assert!(after.contains(&1) && after.contains(&2));
+    let live = after.iter().filter(|&&species| species != 0).count();
+    assert_eq!(live as u32, universe.live_count());
+}
+
+#[wasm_bindgen_test]
+pub fn test_object_count() {
+    let mut universe = Universe::new_dead(10, 10).unwrap();
+    assert_eq!(universe.object_count(), 0);
+
+    universe.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)]);
+    universe.set_cells(&[(6, 6), (6, 7), (7, 6), (7, 7)]);
+    assert_eq!(universe.object_count(), 2);
+
+    // Touching diagonally joins the blocks into one object.
+    universe.set_cells(&[(3, 3), (4, 4), (5, 5)]);
+    assert_eq!(universe.object_count(), 1);
+
+    // So does touching across a wrapping edge.
+    let mut universe = Universe::new_dead(10, 10).unwrap();
+    universe.set_cells(&[(4, 0), (4, 1), (5, 0), (5, 1)]);
+    universe.set_cells(&[(4, 8), (4, 9), (5, 8), (5, 9)]);
+    assert_eq!(universe.object_count(), 1);
+    universe.set_boundary_mode(BoundaryMode::Dead);
+    assert_eq!(universe.object_count(), 2);
+}
+
+#[wasm_bindgen_test]
+pub fn test_run_until_change_delta() {
+    // The glider changes 4 cells every generation until it reaches the
+    // block in generation 60.
+    let mut universe = Universe::new_dead(32, 32).unwrap();
+    universe.insert_glider(3, 3, GliderDirection::SE);
+    universe.set_cells(&[(20, 20), (20, 21), (21, 20), (21, 21)]);
+    assert_eq!(universe.run_until_change_delta(200), 60);
+    assert_eq!(universe.generation(), 60);
+
+    // Carrying on, the wreckage keeps changing differently each time.
+    assert_eq!(universe.run_until_change_delta(200), 1);
+
+    // The cap stops a glider flying through empty space.
+    let mut universe = Universe::new_dead(32, 32).unwrap();
+    universe.insert_glider(3, 3, GliderDirection::SE);
+    assert_eq!(universe.run_until_change_delta(50), 50);
+}
+
+#[wasm_bindgen_test]
+pub fn test_info() {
+    let mut universe = Universe::new_dead(6, 8).unwrap();
+    universe.insert_glider(2, 2, GliderDirection::SE);
+    universe.tick();
+    assert_eq!(
+        universe.info(),
+        Info {
+            width: 8,
+            height: 6,
+            generation: 1,
+            population: 5,
+        }
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_set_from_bits() {
+    let mut universe = Universe::new(8, 8).unwrap();
+
+    // A 3x5 board with the middle column alive: cells 2, 7 and 12, the last
+    // one in the second byte.
+    universe
+        .set_from_bits(&[0b1000_0100, 0b0001_0000], 5, 3)
+        .unwrap();
+    assert_eq!((universe.height(), universe.width()), (3, 5));
+    assert_eq!(universe.live_cells(), vec![2, 7, 12]);
+    assert_eq!(universe.live_count(), 3);
+
+    // Padding bits past the last cell are ignored.
+    universe
+        .set_from_bits(&[0b1000_0100, 0b1001_0000], 5, 3)
+        .unwrap();
+    assert_eq!(universe.live_cells(), vec![2, 7, 12]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_set_from_bits_wrong_length_rejected() {
+    let mut universe = Universe::new(8, 8).unwrap();
+    assert!(universe.set_from_bits(&[0; 1], 5, 3).is_err());
+    assert!(universe.set_from_bits(&[0; 3], 5, 3).is_err());
+    assert!(universe.set_from_bits(&[], 0, 3).is_err());
+    assert_eq!((universe.height(), universe.width()), (8, 8));
+}
+
+#[wasm_bindgen_test]
+pub fn test_to_bits_round_trip() {
+    let mut universe = Universe::new_dead(13, 11).unwrap();
+    universe.randomize(0.5, 31);
+    let bits = universe.to_bits();
+    assert_eq!(bits.len(), 18);
+    // The 143 cells leave the top bit of the last byte as padding.
+    assert_eq!(bits[17] & 0b1000_0000, 0);
+
+    let mut restored = Universe::new_dead(1, 1).unwrap();
+    restored
+        .set_from_bits(&bits, universe.width(), universe.height())
+        .unwrap();
+    assert!(restored.equals(&universe));
+    assert_eq!(restored.live_count(), universe.live_count());
+}
+
+#[wasm_bindgen_test]
+pub fn test_lifespan() {
+    // The R-pentomino settles after 1103 generations on an unbounded grid.
+    // With dead edges far enough away, its escaping gliders simply die.
+    let mut universe = Universe::new_dead(128, 128).unwrap();
+    universe.set_boundary_mode(BoundaryMode::Dead);
+    universe.insert_rle(64, 64, "b2o$2o$bo!").unwrap();
+    let lifespan = universe.lifespan(3000);
+    assert!((1000..=1200).contains(&lifespan), "lifespan {}", lifespan);
+    assert_eq!(universe.generation(), 0);
+    assert_eq!(universe.live_count(), 5);
+
+    let mut universe = Universe::new_dead(16, 16).unwrap();
+    universe.set_cells(&[(4, 4), (4, 5), (5, 4), (5, 5)]);
+    assert_eq!(universe.lifespan(100), 0);
+
+    // A lone glider on a torus only repeats once it has flown all the way
+    // around.
+    let mut universe = Universe::new_dead(16, 16).unwrap();
+    universe.insert_glider(8, 8, GliderDirection::SE);
+    assert_eq!(universe.lifespan(10), 10);
+}
+
+#[wasm_bindgen_test]
+pub fn test_oversized_universe_rejected() {
+    // 100000 * 100000 overflows a u32.
+    assert!(Universe::new(100_000, 100_000).is_err());
+    assert!(Universe::new_dead(u32::MAX, 2).is_err());
+
+    let mut universe = Universe::new_dead(8, 8).unwrap();
+    assert!(universe.resize(100_000, 100_000).is_err());
+    assert!(universe.resize(0, 8).is_err());
+    assert_eq!((universe.height(), universe.width()), (8, 8));
+
+    set_max_cells(100);
+    assert!(Universe::new_dead(10, 10).is_ok());
+    assert!(Universe::new_dead(10, 11).is_err());
+    assert!(universe.resize(11, 10).is_err());
+    set_max_cells(1 << 26);
+}
+
 #[wasm_bindgen_test]
 fn pass() {
     assert_eq!(1 + 1, 2);
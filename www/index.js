@@ -1,6 +1,6 @@
 // Import the WebAssembly memory at the top of the file.
 import { memory } from "wasm-game-of-life/wasm_game_of_life_bg";
-import { Universe, Cell } from "wasm-game-of-life";
+import { GliderDirection, Universe } from "wasm-game-of-life";
 
 const fps = new class {
     constructor() {
@@ -80,7 +80,7 @@ canvas.addEventListener("click", event => {
     const col = Math.min(Math.floor(canvasLeft / (CELL_SIZE + 1)), WIDTH - 1);
 
     if (meta) {
-        universe.insert_glider(row, col);
+        universe.insert_glider(row, col, GliderDirection.SE);
     } else if (shift) {
         universe.insert_pulsar(row, col);
     } else {
@@ -94,8 +94,7 @@ canvas.addEventListener("click", event => {
 const newRandomButton = document.getElementById("new-random");
 newRandomButton.addEventListener("click", () => {
     universe = Universe.new_random(HEIGHT, WIDTH);
-    generation = 1;
-    genCounter.innerText = generation;
+    genCounter.innerText = universe.generation();
     if (isPaused()) {
         drawGrid();
         drawCells();
@@ -105,8 +104,16 @@ newRandomButton.addEventListener("click", () => {
 const newDeadButton = document.getElementById("new-dead");
 newDeadButton.addEventListener("click", () => {
     universe = Universe.new_dead(HEIGHT, WIDTH);
-    generation = 1;
-    genCounter.innerText = generation;
+    genCounter.innerText = universe.generation();
+    if (isPaused()) {
+        drawGrid();
+        drawCells();
+    }
+});
+
+const clearButton = document.getElementById("clear");
+clearButton.addEventListener("click", () => {
+    universe.clear();
     if (isPaused()) {
         drawGrid();
         drawCells();
@@ -114,8 +121,7 @@ newDeadButton.addEventListener("click", () => {
 });
 
 const genCounter = document.getElementById("gen-counter");
-let generation = 1;
-genCounter.innerText = generation;
+genCounter.innerText = universe.generation();
 
 const genSpeedInput = document.getElementById("gen-speed");
 
@@ -142,9 +148,13 @@ const getIndex = (row, column) => {
     return row * WIDTH + column;
 }
 
+const bitIsSet = (n, words) => {
+    return (words[n >> 5] & (1 << (n & 31))) !== 0;
+}
+
 const drawCells = () => {
-    const cellsPtr = universe.cells();
-    const cells = new Uint8Array(memory.buffer, cellsPtr, WIDTH * HEIGHT);
+    const cellsPtr = universe.cells_bits();
+    const cells = new Uint32Array(memory.buffer, cellsPtr, Math.ceil(WIDTH * HEIGHT / 32));
 
     ctx.beginPath();
 
@@ -154,7 +164,7 @@ const drawCells = () => {
         for (let col = 0; col < WIDTH; col++) {
             const idx = getIndex(row, col);
 
-            if (cells[idx] !== Cell.Alive) {
+            if (!bitIsSet(idx, cells)) {
                 continue;
             }
 
@@ -173,7 +183,7 @@ const drawCells = () => {
         for (let col = 0; col < WIDTH; col++) {
             const idx = getIndex(row, col);
 
-            if (cells[idx] !== Cell.Dead) {
+            if (bitIsSet(idx, cells)) {
                 continue;
             }
 
@@ -201,10 +211,8 @@ const renderLoop = () => {
 
     // debugger;
     const genSpeed = parseInt(genSpeedInput.value);
-    for (let i = 0; i < genSpeed; i++) {
-        universe.tick();
-        genCounter.innerText = ++generation;
-    }
+    universe.tick_n(genSpeed);
+    genCounter.innerText = universe.generation();
 
     drawGrid();
     drawCells();